@@ -0,0 +1,160 @@
+use crate::constants::{MAP_HEIGHT, MAP_WIDTH};
+use crate::enemy::Enemy;
+use crate::game::Game;
+use crate::object::Object;
+use crate::pickup::PickupCategory;
+use crate::player::Player;
+use crate::Tcod;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fmt;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Task {
+  Rest,
+  Explore,
+  /// keep moving in a direction, `open` counts the floor tiles around the previous step
+  Run { dx: i32, dy: i32, open: usize },
+  /// walk to a known tile
+  Travel { x: i32, y: i32 },
+}
+
+impl fmt::Display for Task {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Task::Rest => write!(f, "resting"),
+      Task::Explore => write!(f, "exploring"),
+      Task::Run { .. } => write!(f, "running"),
+      Task::Travel { .. } => write!(f, "travelling"),
+    }
+  }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+/// A task the player keeps doing turn after turn, until it's done or something dangerous happens.
+pub struct Activity {
+  pub task: Task,
+  // what the player looked like last turn, to notice when things go wrong
+  hp: i32,
+  messages: usize,
+}
+
+impl Activity {
+  pub fn new(task: Task, game: &Game, player: &Player) -> Self {
+    Activity {
+      task,
+      hp: player.get_fighter().map_or(0, |f| f.hp),
+      messages: game.messages.count(),
+    }
+  }
+
+  /// the reason to stop right away, if there is one
+  pub fn danger(&self, tcod: &Tcod, game: &Game, player: &Player, enemies: &[Enemy]) -> Option<&'static str> {
+    if hostile_in_view(tcod, enemies) {
+      Some("You spot an enemy!")
+    } else if player.get_fighter().map_or(0, |f| f.hp) < self.hp {
+      Some("You are hurt!")
+    } else if game.messages.iter().skip(self.messages).any(|(_, color)| *color == tcod::colors::RED) {
+      Some("Something is wrong.")
+    } else {
+      None
+    }
+  }
+
+  /// remember the state of the player after a turn
+  pub fn watch(&mut self, game: &Game, player: &Player) {
+    self.hp = player.get_fighter().map_or(0, |f| f.hp);
+    self.messages = game.messages.count();
+  }
+}
+
+/// true if a monster that isn't peaceful can be seen
+pub fn hostile_in_view(tcod: &Tcod, enemies: &[Enemy]) -> bool {
+  enemies
+    .iter()
+    .any(|enemy| enemy.get_ai().is_some() && !enemy.is_shopkeeper() && tcod.fov.is_in_fov(enemy.get_x(), enemy.get_y()))
+}
+
+const DIRECTIONS: [(i32, i32); 8] = [(-1, 0), (1, 0), (0, -1), (0, 1), (-1, -1), (1, -1), (-1, 1), (1, 1)];
+
+/// how many of the tiles around a position can be walked on
+pub fn open_neighbours(game: &Game, x: i32, y: i32) -> usize {
+  DIRECTIONS
+    .iter()
+    .map(|(dx, dy)| (x + dx, y + dy))
+    .filter(|&(x, y)| x >= 0 && y >= 0 && x < MAP_WIDTH && y < MAP_HEIGHT && !game.map[x as usize][y as usize].is_blocked())
+    .count()
+}
+
+/// true if the way ahead is open, nothing lies where the player stands, and the surroundings
+/// look like the previous step: a change means a junction, a doorway or a room's edge
+pub fn keep_running(game: &Game, player: &Player, enemies: &[Enemy], collectibles: &[Object], dx: i32, dy: i32, open: usize) -> bool {
+  if open_neighbours(game, player.get_x(), player.get_y()) != open {
+    return false;
+  }
+  let (x, y) = (player.get_x() + dx, player.get_y() + dy);
+  if x < 0 || y < 0 || x >= MAP_WIDTH || y >= MAP_HEIGHT || game.map[x as usize][y as usize].is_blocked() {
+    return false;
+  }
+  let occupied = enemies.iter().any(|enemy| enemy.get_fighter().is_some() && enemy.pos() == (x, y));
+  let something_here = collectibles.iter().any(|object| object.pos() == player.pos());
+  !occupied && !something_here
+}
+
+/// the direction of the first step on the shortest known path to a goal,
+/// walking only over explored floor that no monster stands on
+pub fn first_step<F>(game: &Game, enemies: &[Enemy], start: (i32, i32), is_goal: F) -> Option<(i32, i32)>
+where
+  F: Fn(i32, i32) -> bool,
+{
+  // the tile each reached tile was entered from
+  let mut came_from = vec![vec![None; MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+  let mut queue = VecDeque::new();
+  came_from[start.0 as usize][start.1 as usize] = Some(start);
+  queue.push_back(start);
+  while let Some((x, y)) = queue.pop_front() {
+    for &(dx, dy) in &DIRECTIONS {
+      let (next_x, next_y) = (x + dx, y + dy);
+      if next_x < 0 || next_y < 0 || next_x >= MAP_WIDTH || next_y >= MAP_HEIGHT {
+        continue;
+      }
+      let (tile_x, tile_y) = (next_x as usize, next_y as usize);
+      if came_from[tile_x][tile_y].is_some() {
+        continue;
+      }
+      came_from[tile_x][tile_y] = Some((x, y));
+      if is_goal(next_x, next_y) {
+        // walk the path back to the tile next to the start
+        let mut step = (next_x, next_y);
+        while let Some(previous) = came_from[step.0 as usize][step.1 as usize] {
+          if previous == start {
+            return Some((step.0 - start.0, step.1 - start.1));
+          }
+          step = previous;
+        }
+      }
+      let tile = &game.map[tile_x][tile_y];
+      let occupied = enemies.iter().any(|enemy| enemy.get_fighter().is_some() && enemy.pos() == (next_x, next_y));
+      if tile.is_explored() && !tile.is_blocked() && !occupied {
+        queue.push_back((next_x, next_y));
+      }
+    }
+  }
+  None
+}
+
+/// the next step towards an unexplored tile, or towards an item worth picking up
+pub fn explore_step(game: &Game, player: &Player, enemies: &[Enemy], collectibles: &[Object]) -> Option<(i32, i32)> {
+  let wanted = collectibles
+    .iter()
+    .filter(|object| {
+      object.item.map_or(false, |item| game.auto_pickup.contains(&PickupCategory::of(item)))
+        && game.map[object.x as usize][object.y as usize].is_explored()
+        && player.can_pick_up(object, game)
+    })
+    .map(|object| object.pos())
+    .collect::<Vec<_>>();
+  first_step(game, enemies, player.pos(), |x, y| {
+    !game.map[x as usize][y as usize].is_explored() || wanted.contains(&(x, y))
+  })
+}
@@ -0,0 +1,122 @@
+use crate::damage::Resistances;
+use crate::equipment::Ammo;
+use crate::equipment::Slot;
+use crate::object::Item;
+use crate::object::Object;
+use crate::spell::Spell;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// The background chosen at the start of a game, it never changes afterwards.
+pub enum Class {
+  Warrior,
+  Rogue,
+  Mage,
+}
+
+pub const CLASSES: &[Class] = &[Class::Warrior, Class::Rogue, Class::Mage];
+
+/// Starting statistics of a class.
+pub struct ClassStats {
+  pub max_hp: i32,
+  pub power: i32,
+  pub defense: i32,
+  pub max_mana: i32,
+  pub resistances: Resistances,
+}
+
+impl Class {
+  pub fn description(self) -> &'static str {
+    match self {
+      Class::Warrior => "tough, resists blows and bashes doors open",
+      Class::Rogue => "nimble, picks locks safely and starts with a sling",
+      Class::Mage => "frail, knows every scroll and starts with a wand",
+    }
+  }
+
+  pub fn stats(self) -> ClassStats {
+    match self {
+      Class::Warrior => ClassStats {
+        max_hp: 120,
+        power: 5,
+        defense: 2,
+        max_mana: 10,
+        resistances: Resistances { physical: 10, ..Default::default() },
+      },
+      Class::Rogue => ClassStats {
+        max_hp: 90,
+        power: 4,
+        defense: 1,
+        max_mana: 20,
+        resistances: Resistances { poison: 25, ..Default::default() },
+      },
+      Class::Mage => ClassStats {
+        max_hp: 70,
+        power: 3,
+        defense: 0,
+        max_mana: 50,
+        resistances: Resistances { fire: 20, lightning: 20, cold: 20, ..Default::default() },
+      },
+    }
+  }
+
+  /// items carried from the start, the first equipment for each slot is worn right away
+  pub fn starting_items(self) -> Vec<Object> {
+    match self {
+      Class::Warrior => vec![
+        Object::create_sword(0, 0),
+        Object::create_equipment(0, 0, '[', "shield", Slot::LeftHand, 0, 1),
+        Object::create_ration(0, 0),
+      ],
+      Class::Rogue => vec![
+        // the sling is ready to shoot, the dagger waits in the pack
+        Object::create_sling(0, 0),
+        Object::create_equipment(0, 0, '/', "dagger", Slot::RightHand, 2, 0),
+        Object::create_ammo(0, 0, Ammo::Stone, 20),
+        Object::create_potion(0, 0),
+        Object::create_ration(0, 0),
+      ],
+      Class::Mage => vec![
+        Object::create_wand(0, 0, Item::LightningWand, "wand of lightning"),
+        Object::create_identify_scroll(0, 0),
+        Object::create_potion(0, 0),
+        Object::create_ration(0, 0),
+      ],
+    }
+  }
+
+  pub fn starting_spells(self) -> Vec<Spell> {
+    match self {
+      Class::Mage => vec![Spell::MagicMissile],
+      Class::Warrior | Class::Rogue => vec![],
+    }
+  }
+
+  /// items the class recognizes from the start, besides its starting items
+  pub fn known_items(self) -> Vec<Object> {
+    match self {
+      Class::Mage => vec![
+        Object::create_lighting_bolt(0, 0),
+        Object::create_confuse_potion(0, 0),
+        Object::create_fireball(0, 0),
+        Object::create_identify_scroll(0, 0),
+        Object::create_recharge_scroll(0, 0),
+        Object::create_remove_curse_scroll(0, 0),
+        Object::create_scroll(0, 0, Item::MassConfusion, "scroll of mass confusion"),
+        Object::create_scroll(0, 0, Item::Teleport, "scroll of teleportation"),
+      ],
+      Class::Warrior | Class::Rogue => vec![],
+    }
+  }
+}
+
+impl fmt::Display for Class {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Class::Warrior => write!(f, "Warrior"),
+      Class::Rogue => write!(f, "Rogue"),
+      Class::Mage => write!(f, "Mage"),
+    }
+  }
+}
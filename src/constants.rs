@@ -16,6 +16,14 @@ pub const COLOR_LIGHT_GROUND: Color = Color { r: 200, g: 180, b: 50 };
 pub const COLOR_DARK_WALL: Color = Color { r: 0, g: 0, b: 100 };
 pub const COLOR_LIGHT_WALL: Color = Color { r: 130, g: 110, b: 50 };
 
+pub const COLOR_DOOR: Color = Color { r: 160, g: 90, b: 30 };
+
+// added to the tiles a target can be chosen among, and to those an area effect would hit
+pub const COLOR_TARGET_RANGE: Color = Color { r: 0, g: 0, b: 60 };
+pub const COLOR_TARGET_AREA: Color = Color { r: 90, g: 0, b: 0 };
+pub const COLOR_CURSOR: Color = Color { r: 0, g: 200, b: 200 };
+pub const CURSOR_JUMP: i32 = 5; // tiles the cursor moves when shift is held
+
 
 // size of the map (duplicated from game, TODO create a constant file)
 pub const MAP_WIDTH: i32 = 80;
@@ -35,6 +43,18 @@ pub const ROOM_MAX_SIZE: i32 = 10;
 pub const ROOM_MIN_SIZE: i32 = 6;
 pub const MAX_ROOMS: i32 = 30;
 
+// vaults
+pub const VAULT_CHANCE: f32 = 0.35;
+pub const VAULT_MIN_SIZE: i32 = 4;
+pub const VAULT_MAX_SIZE: i32 = 6;
+pub const VAULT_ATTEMPTS: i32 = 50;
+pub const VAULT_LOOT_BONUS: u32 = 3; // vault loot is rolled as if it was this much deeper
+pub const LOCKPICK_CHANCE: f32 = 0.3;
+pub const LOCKPICK_TRAP_CHANCE: f32 = 0.25;
+pub const LOCKPICK_TRAP_DAMAGE: i32 = 6;
+pub const BASH_CHANCE_PER_POWER: f32 = 0.04;
+pub const BASH_DAMAGE: i32 = 3;
+
 pub const MAX_INVENTORY: usize = 26;
 
 
@@ -50,11 +70,76 @@ pub const CONFUSE_RANGE: i32 = 8;
 pub const CONFUSE_NUM_TURNS: i32 = 10;
 pub const FIREBALL_RADIUS: i32 = 3;
 pub const FIREBALL_DAMAGE: i32 = 25;
-
+pub const MASS_CONFUSION_RADIUS: i32 = 8;
+pub const POISON_NUM_TURNS: i32 = 8;
+pub const POISON_DAMAGE: i32 = 2;
+pub const AMMO_BREAK_CHANCE: f32 = 0.3;
+pub const THROW_RANGE: i32 = 6;
+pub const THROWN_WEAPON_DAMAGE: i32 = 6;
+pub const THROWN_ITEM_DAMAGE: i32 = 1;
+pub const POTION_SPLASH_RADIUS: i32 = 1;
+pub const WAND_MIN_CHARGES: i32 = 3;
+pub const WAND_MAX_CHARGES: i32 = 6;
+pub const RECHARGE_AMOUNT: i32 = 4;
+pub const DIG_RANGE: i32 = 8;
+pub const CURSE_CHANCE: f32 = 0.15;
+pub const BLESSING_CHANCE: f32 = 0.1;
+pub const CURSE_PENALTY: i32 = 2;
+pub const BLESSING_BONUS: i32 = 1;
+
+
+// gold and shops
+pub const MONSTER_GOLD_CHANCE: f32 = 0.4;
+pub const SHOP_CHANCE: f32 = 0.3;
+pub const SELL_PRICE_RATIO: i32 = 2; // items are bought back at half their price
+pub const SHOP_WIDTH: i32 = 60;
+
+// weight and encumbrance
+pub const CARRY_CAPACITY_BASE: i32 = 150;
+pub const CARRY_CAPACITY_PER_POWER: i32 = 25;
+pub const MAX_LOAD_FACTOR: i32 = 2; // nothing more can be picked up past twice the capacity
+pub const ACTION_COST: i32 = 100;
+pub const BURDENED_ACTION_COST: i32 = 150; // monsters get half a turn more while the player is burdened
+
+// hunger clock
+pub const START_NUTRITION: i32 = 1000;
+pub const MAX_NUTRITION: i32 = 1500;
+pub const NUTRITION_PER_TURN: i32 = 1;
+pub const HUNGRY_NUTRITION: i32 = 300;
+pub const WEAK_NUTRITION: i32 = 100;
+pub const STARVATION_DAMAGE: i32 = 1;
+pub const RATION_NUTRITION: i32 = 800;
+pub const CORPSE_NUTRITION: i32 = 300;
+
+// natural regeneration
+pub const HP_REGEN_TURNS: i32 = 5; // hit points come back every few turns
+pub const HP_REGEN_DIVISOR: i32 = 50; // a fiftieth of the maximum, at least one
+
+// mana and spells
+pub const MANA_REGEN_TURNS: i32 = 4; // one point of mana every few turns
+pub const MANA_PER_LEVEL: i32 = 5;
+pub const MAGIC_MISSILE_RANGE: i32 = 6;
+pub const MAGIC_MISSILE_DAMAGE: i32 = 12;
+pub const MINOR_HEAL_AMOUNT: i32 = 20;
+pub const SPELL_SCREEN_WIDTH: i32 = 40;
+
+// perks
+pub const TOUGH_SKIN_RESISTANCE: i32 = 15;
+pub const GOURMAND_BONUS: i32 = 50; // percent
+pub const CLEAVE_DAMAGE: i32 = 50; // percent of the main attack
+pub const WEAPON_MASTER_BONUS: i32 = 2;
+pub const ELEMENT_MASTERY_BONUS: i32 = 50; // percent
+pub const SHARPSHOOTER_BONUS: i32 = 2;
+pub const ASSASSIN_MULTIPLIER: i32 = 2;
 
 // experience and level-ups
 pub const LEVEL_UP_BASE: i32 = 200;
 pub const LEVEL_UP_FACTOR: i32 = 150;
 
 pub const LEVEL_SCREEN_WIDTH: i32 = 40;
-pub const CHARACTER_SCREEN_WIDTH: i32 = 30;
\ No newline at end of file
+pub const CHARACTER_SCREEN_WIDTH: i32 = 30;
+pub const CLASS_SCREEN_WIDTH: i32 = 60;
+pub const PERK_SCREEN_WIDTH: i32 = 60;
+pub const HELP_SCREEN_WIDTH: i32 = 50;
+pub const GAME_OVER_SCREEN_WIDTH: i32 = 60;
+pub const GAME_OVER_MESSAGES: usize = 5; // last messages shown when the player dies
\ No newline at end of file
@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+  Physical,
+  Fire,
+  Lightning,
+  Cold,
+  Poison,
+}
+
+pub const DAMAGE_TYPES: &[DamageType] = &[
+  DamageType::Physical,
+  DamageType::Fire,
+  DamageType::Lightning,
+  DamageType::Cold,
+  DamageType::Poison,
+];
+
+impl fmt::Display for DamageType {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      DamageType::Physical => write!(f, "physical"),
+      DamageType::Fire => write!(f, "fire"),
+      DamageType::Lightning => write!(f, "lightning"),
+      DamageType::Cold => write!(f, "cold"),
+      DamageType::Poison => write!(f, "poison"),
+    }
+  }
+}
+
+/// Resistances in percent: 100 means immune, a negative value means the
+/// creature takes extra damage of that type.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Resistances {
+  pub physical: i32,
+  pub fire: i32,
+  pub lightning: i32,
+  pub cold: i32,
+  pub poison: i32,
+}
+
+impl Resistances {
+  pub fn get(&self, damage_type: DamageType) -> i32 {
+    match damage_type {
+      DamageType::Physical => self.physical,
+      DamageType::Fire => self.fire,
+      DamageType::Lightning => self.lightning,
+      DamageType::Cold => self.cold,
+      DamageType::Poison => self.poison,
+    }
+  }
+
+  pub fn add(&self, other: &Resistances) -> Resistances {
+    Resistances {
+      physical: self.physical + other.physical,
+      fire: self.fire + other.fire,
+      lightning: self.lightning + other.lightning,
+      cold: self.cold + other.cold,
+      poison: self.poison + other.poison,
+    }
+  }
+
+  pub fn total(&self) -> i32 {
+    self.physical + self.fire + self.lightning + self.cold + self.poison
+  }
+
+  /// return the damage left once this resistance has been applied
+  pub fn apply(&self, damage: i32, damage_type: DamageType) -> i32 {
+    // resistances never go above immunity
+    let resistance = self.get(damage_type).min(100);
+    damage * (100 - resistance) / 100
+  }
+}
@@ -0,0 +1,334 @@
+use crate::constants::*;
+use crate::damage::DamageType;
+use crate::enemy::Enemy;
+use crate::game::{reachable_tiles, Game};
+use crate::object::Item;
+use crate::object::Object;
+use crate::player::Player;
+use crate::target_tile;
+use crate::Tcod;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tcod::colors::Color;
+
+/// How an item chooses who its effects apply to.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Targeting {
+  /// whoever uses the item
+  User,
+  /// the closest visible monster within range
+  Nearest { range: i32 },
+  /// a monster picked with the mouse
+  Monster { range: i32 },
+  /// every monster around a tile picked with the mouse
+  Area { radius: i32 },
+  /// every visible monster around the user
+  AroundUser { radius: i32 },
+}
+
+/// A building block of what an item does to each of its targets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Effect {
+  Damage { amount: i32, damage_type: DamageType },
+  Heal { amount: i32 },
+  Status { status: Status, turns: i32 },
+  Teleport,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Status {
+  Confused,
+  Poisoned,
+}
+
+pub struct ItemEffect {
+  pub targeting: Targeting,
+  pub effects: &'static [Effect],
+  /// shown once when the item is used, before the effects are applied
+  pub message: Option<(&'static str, Color)>,
+}
+
+impl ItemEffect {
+  /// what the item does and to whom, for the examine screen
+  pub fn summary(&self) -> String {
+    let effects = self
+      .effects
+      .iter()
+      .map(|effect| match *effect {
+        Effect::Damage { amount, damage_type } => format!("deals {} {} damage", amount, damage_type),
+        Effect::Heal { amount } => format!("heals {} hit points", amount),
+        Effect::Status { status: Status::Confused, turns } => format!("confuses for {} turns", turns),
+        Effect::Status { status: Status::Poisoned, turns } => format!("poisons for {} turns", turns),
+        Effect::Teleport => "teleports".to_string(),
+      })
+      .collect::<Vec<_>>()
+      .join(", ");
+    let targets = match self.targeting {
+      Targeting::User => "yourself".to_string(),
+      Targeting::Nearest { range } => format!("the closest enemy within {} tiles", range),
+      Targeting::Monster { range } => format!("an enemy of your choice within {} tiles", range),
+      Targeting::Area { radius } => format!("every enemy within {} tiles of a chosen tile", radius),
+      Targeting::AroundUser { radius } => format!("every visible enemy within {} tiles", radius),
+    };
+    format!("Effect: {}\nTarget: {}", effects, targets)
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Target {
+  User,
+  Monster(usize),
+}
+
+pub const LIGHTNING: ItemEffect = ItemEffect {
+  targeting: Targeting::Nearest { range: LIGHTNING_RANGE },
+  effects: &[Effect::Damage { amount: LIGHTNING_DAMAGE, damage_type: DamageType::Lightning }],
+  message: Some(("A lightning bolt strikes with a loud thunder!", tcod::colors::LIGHT_BLUE)),
+};
+
+pub const CONFUSION: ItemEffect = ItemEffect {
+  targeting: Targeting::Monster { range: CONFUSE_RANGE },
+  effects: &[Effect::Status { status: Status::Confused, turns: CONFUSE_NUM_TURNS }],
+  message: None,
+};
+
+pub const FIREBALL: ItemEffect = ItemEffect {
+  targeting: Targeting::Area { radius: FIREBALL_RADIUS },
+  effects: &[Effect::Damage { amount: FIREBALL_DAMAGE, damage_type: DamageType::Fire }],
+  message: Some(("The fireball explodes, burning everything around it!", tcod::colors::ORANGE)),
+};
+
+pub const MASS_CONFUSION: ItemEffect = ItemEffect {
+  targeting: Targeting::AroundUser { radius: MASS_CONFUSION_RADIUS },
+  effects: &[Effect::Status { status: Status::Confused, turns: CONFUSE_NUM_TURNS }],
+  message: Some(("A wave of dizziness washes over the room!", tcod::colors::LIGHT_GREEN)),
+};
+
+pub const TELEPORT: ItemEffect = ItemEffect {
+  targeting: Targeting::User,
+  effects: &[Effect::Teleport],
+  message: None,
+};
+
+// every consumable that is defined only by its effects
+const ITEM_EFFECTS: &[(Item, ItemEffect)] = &[
+  (
+    Item::Heal,
+    ItemEffect {
+      targeting: Targeting::User,
+      effects: &[Effect::Heal { amount: HEAL_AMOUNT }],
+      message: None,
+    },
+  ),
+  (
+    Item::ConfusionPotion,
+    ItemEffect {
+      targeting: Targeting::User,
+      effects: &[Effect::Status { status: Status::Confused, turns: CONFUSE_NUM_TURNS }],
+      message: None,
+    },
+  ),
+  (
+    Item::PoisonPotion,
+    ItemEffect {
+      targeting: Targeting::User,
+      effects: &[Effect::Status { status: Status::Poisoned, turns: POISON_NUM_TURNS }],
+      message: None,
+    },
+  ),
+  (Item::Lightning, LIGHTNING),
+  (Item::Confuse, CONFUSION),
+  (Item::Fireball, FIREBALL),
+  (Item::MassConfusion, MASS_CONFUSION),
+  (Item::Teleport, TELEPORT),
+  (Item::LightningWand, LIGHTNING),
+  (Item::ConfusionWand, CONFUSION),
+  (Item::FireballWand, FIREBALL),
+];
+
+pub fn item_effect(item: Item) -> Option<&'static ItemEffect> {
+  ITEM_EFFECTS
+    .iter()
+    .find(|(kind, _)| *kind == item)
+    .map(|(_, item_effect)| item_effect)
+}
+
+/// select the targets and apply the effects, returns false if the item wasn't used
+pub fn use_item_effect(item_effect: &ItemEffect, user: &mut Player, game: &mut Game, tcod: &mut Tcod, collectibles: &[Object], enemies: &mut [Enemy]) -> bool {
+  // don't waste a healing item at full health
+  let heals_only = item_effect.effects.iter().all(|effect| matches!(effect, Effect::Heal { .. }));
+  if item_effect.targeting == Targeting::User && heals_only && user.get_fighter().map_or(false, |f| f.hp == f.max_hp) {
+    game.messages.add("You are already at full health.", tcod::colors::RED);
+    return false;
+  }
+  let targets = match select_targets(item_effect.targeting, user, game, tcod, collectibles, enemies) {
+    Some(targets) => targets,
+    None => return false,
+  };
+  if let Some((message, color)) = item_effect.message {
+    game.messages.add(message, color);
+  }
+  apply_effects(item_effect.effects, &targets, user, game, enemies);
+  true
+}
+
+/// find or ask for the targets, None when the player cancels or nothing can be targeted
+pub fn select_targets(targeting: Targeting, user: &Player, game: &mut Game, tcod: &mut Tcod, collectibles: &[Object], enemies: &[Enemy]) -> Option<Vec<Target>> {
+  match targeting {
+    Targeting::User => Some(vec![Target::User]),
+    Targeting::Nearest { range } => match user.closest_monster(tcod, enemies, range) {
+      Some(monster_id) => Some(vec![Target::Monster(monster_id)]),
+      None => {
+        game.messages.add("No enemy is close enough to strike.", tcod::colors::RED);
+        None
+      }
+    },
+    Targeting::Monster { range } => {
+      game.messages.add(
+        "Choose an enemy with the mouse or the cursor and Enter, Tab to cycle enemies, Escape to cancel.",
+        tcod::colors::LIGHT_CYAN,
+      );
+      user
+        .target_monster(tcod, game, enemies, collectibles, Some(range as f32))
+        .map(|monster_id| vec![Target::Monster(monster_id)])
+    }
+    Targeting::Area { radius } => {
+      game.messages.add(
+        "Choose a target tile with the mouse or the cursor and Enter, Tab to cycle enemies, Escape to cancel.",
+        tcod::colors::LIGHT_CYAN,
+      );
+      let (x, y) = target_tile(tcod, game, user, enemies, collectibles, None, Some(radius))?;
+      Some(monsters_around(x, y, radius, enemies))
+    }
+    Targeting::AroundUser { radius } => {
+      let targets = monsters_around(user.get_x(), user.get_y(), radius, enemies)
+        .into_iter()
+        .filter(|target| match *target {
+          Target::Monster(id) => tcod.fov.is_in_fov(enemies[id].get_x(), enemies[id].get_y()),
+          Target::User => false,
+        })
+        .collect::<Vec<_>>();
+      if targets.is_empty() {
+        game.messages.add("There is no enemy around you.", tcod::colors::RED);
+        return None;
+      }
+      Some(targets)
+    }
+  }
+}
+
+/// every living monster within the radius of a tile
+pub fn monsters_around(x: i32, y: i32, radius: i32, enemies: &[Enemy]) -> Vec<Target> {
+  enemies
+    .iter()
+    .enumerate()
+    .filter(|(_, enemy)| enemy.get_fighter().is_some() && enemy.distance(x, y) <= radius as f32)
+    .map(|(id, _)| Target::Monster(id))
+    .collect()
+}
+
+/// apply each effect to each target in turn, the user earns the experience of any kill
+pub fn apply_effects(effects: &[Effect], targets: &[Target], user: &mut Player, game: &mut Game, enemies: &mut [Enemy]) {
+  for target in targets {
+    for effect in effects {
+      match *target {
+        Target::User => apply_to_user(*effect, user, game, enemies),
+        Target::Monster(id) => {
+          // a previous effect may already have killed it
+          if enemies[id].get_fighter().is_none() {
+            break;
+          }
+          if let Some(xp) = apply_to_monster(*effect, id, user, game, enemies) {
+            user.gain_xp(xp);
+          }
+        }
+      }
+    }
+  }
+}
+
+fn apply_to_user(effect: Effect, user: &mut Player, game: &mut Game, enemies: &[Enemy]) {
+  match effect {
+    Effect::Damage { amount, damage_type } => {
+      let damage = user.resisted_damage(amount, damage_type, game);
+      if damage > 0 {
+        game.messages.add(format!("You take {} {} damage.", damage, damage_type), tcod::colors::RED);
+        user.take_damage(damage, game);
+      } else {
+        game.messages.add(format!("You shrug off the {}.", damage_type), tcod::colors::WHITE);
+      }
+    }
+    Effect::Heal { amount } => {
+      game.messages.add("Your wounds start to feel better!", tcod::colors::LIGHT_VIOLET);
+      user.heal(amount);
+    }
+    Effect::Status { status: Status::Confused, turns } => {
+      game.messages.add("The room starts spinning around you!", tcod::colors::LIGHT_BLUE);
+      user.confuse(turns);
+    }
+    Effect::Status { status: Status::Poisoned, turns } => {
+      game.messages.add("You feel very sick!", tcod::colors::DARK_GREEN);
+      user.poison(turns);
+    }
+    Effect::Teleport => match random_free_tile(game, user, enemies) {
+      Some((x, y)) => {
+        game.messages.add("You feel yanked in a new direction!", tcod::colors::LIGHT_AZURE);
+        user.set_pos(x, y);
+      }
+      None => game.messages.add("You shimmer for a moment, but stay where you are.", tcod::colors::LIGHT_AZURE),
+    },
+  }
+}
+
+fn apply_to_monster(effect: Effect, id: usize, user: &Player, game: &mut Game, enemies: &mut [Enemy]) -> Option<i32> {
+  if effect == Effect::Teleport {
+    match random_free_tile(game, user, enemies) {
+      Some((x, y)) => {
+        game.messages.add(format!("The {} vanishes!", enemies[id].get_name()), tcod::colors::LIGHT_AZURE);
+        enemies[id].set_pos(x, y);
+      }
+      None => game.messages.add(format!("The {} shimmers for a moment.", enemies[id].get_name()), tcod::colors::LIGHT_AZURE),
+    }
+    return None;
+  }
+  let enemy = &mut enemies[id];
+  match effect {
+    Effect::Damage { amount, damage_type } => {
+      let damage = enemy.resisted_damage(user.item_damage(amount, damage_type), damage_type);
+      if damage > 0 {
+        game.messages.add(
+          format!("The {} takes {} {} damage.", enemy.get_name(), damage, damage_type),
+          tcod::colors::ORANGE,
+        );
+        return enemy.take_damage(damage, game);
+      }
+      game.messages.add(format!("The {} shrugs off the {}.", enemy.get_name(), damage_type), tcod::colors::ORANGE);
+    }
+    Effect::Heal { amount } => {
+      game.messages.add(format!("The {} looks healthier.", enemy.get_name()), tcod::colors::LIGHT_VIOLET);
+      enemy.heal(amount);
+    }
+    Effect::Status { status: Status::Confused, turns } => {
+      game.messages.add(
+        format!("The eyes of {} look vacant, as he starts to stumble around!", enemy.get_name()),
+        tcod::colors::LIGHT_GREEN,
+      );
+      enemy.confuse(turns);
+    }
+    Effect::Status { status: Status::Poisoned, turns } => {
+      game.messages.add(format!("The {} looks very sick.", enemy.get_name()), tcod::colors::DARK_GREEN);
+      enemy.poison(turns);
+    }
+    Effect::Teleport => unreachable!(),
+  }
+  None
+}
+
+/// a random empty tile the player can walk to, so that a teleport never ends behind a locked door
+fn random_free_tile(game: &Game, user: &Player, enemies: &[Enemy]) -> Option<(i32, i32)> {
+  let free = reachable_tiles(user.pos(), &game.map)
+    .into_iter()
+    .filter(|&pos| user.pos() != pos && !enemies.iter().any(|enemy| enemy.get_fighter().is_some() && enemy.pos() == pos))
+    .collect::<Vec<_>>();
+  rand::thread_rng().choose(&free).cloned()
+}
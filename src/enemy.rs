@@ -1,8 +1,12 @@
 extern crate rand;
+use crate::damage::{DamageType, Resistances};
 use crate::game::Game;
 use crate::player::Player;
 use crate::fighter::Fighter;
 use crate::object::Object;
+use crate::object::place_on_floor;
+use crate::constants::MONSTER_GOLD_CHANCE;
+use crate::constants::POISON_DAMAGE;
 
 use crate::Tcod;
 use tcod::colors::Color;
@@ -18,17 +22,21 @@ pub enum Ai {
     previous_ai: Box<Ai>,
     num_turns: i32,
   },
+  Shopkeeper,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Enemy {
   object: Object,
   ai: Option<Ai>,
+  // items for sale, only shopkeepers have some
+  stock: Vec<Object>,
+  poisoned_turns: i32,
 }
 
 
 impl Enemy {
-  pub fn new(x: i32, y: i32, char: char, color: Color, name: &str, max_hp: i32, hp: i32, defense: i32, power: i32, xp: i32) -> Self {
+  pub fn new(x: i32, y: i32, char: char, color: Color, name: &str, max_hp: i32, hp: i32, defense: i32, power: i32, xp: i32, damage_type: DamageType, resistances: Resistances) -> Self {
     let object = Object { 
       x, 
       y,
@@ -42,15 +50,21 @@ impl Enemy {
         hp,
         defense,
         power,
-        xp
+        xp,
+        damage_type,
+        resistances,
       }),
       item: None,
       equipment: None,
-      always_visible: false
+      always_visible: false,
+      quantity: 1,
+      charges: None,
     };
     Enemy {
       object,
-      ai: Some(Ai::Basic)
+      ai: Some(Ai::Basic),
+      stock: vec![],
+      poisoned_turns: 0,
     }
   }
 
@@ -66,7 +80,9 @@ impl Enemy {
       20,
       0,
       4,
-      35
+      35,
+      DamageType::Physical,
+      Resistances::default(),
     )
   }
 
@@ -81,13 +97,55 @@ impl Enemy {
       30,
       2,
       8,
-      100
+      100,
+      DamageType::Physical,
+      // trolls regenerate from most wounds, but not from burns
+      Resistances { fire: -50, ..Default::default() },
     )
   }
 
+  pub fn create_fire_elemental(x: i32, y: i32) -> Self {
+    Enemy::new(
+      x,
+      y,
+      'E',
+      tcod::colors::FLAME,
+      &"fire elemental".to_string(),
+      25,
+      25,
+      1,
+      7,
+      120,
+      DamageType::Fire,
+      Resistances { fire: 100, cold: -50, ..Default::default() },
+    )
+  }
+
+  pub fn create_shopkeeper(x: i32, y: i32, stock: Vec<Object>) -> Self {
+    let mut shopkeeper = Enemy::new(
+      x,
+      y,
+      '@',
+      tcod::colors::YELLOW,
+      &"shopkeeper".to_string(),
+      100,
+      100,
+      5,
+      10,
+      0,
+      DamageType::Physical,
+      Resistances::default(),
+    );
+    shopkeeper.ai = Some(Ai::Shopkeeper);
+    shopkeeper.stock = stock;
+    shopkeeper
+  }
+
   pub fn attack(&mut self, player: &mut Player, game: &mut Game) {
     // a simple formula for attack damage
-    let damage = self.get_fighter().map_or(0, |f| f.power) - player.get_fighter().map_or(0, |f| f.defense);
+    let damage = self.get_fighter().map_or(0, |f| f.power) - player.defense(game);
+    let damage_type = self.get_fighter().map_or(DamageType::Physical, |f| f.damage_type);
+    let damage = player.resisted_damage(damage, damage_type, game);
     if damage > 0 {
       // make the target take some damage
       game.messages.add(
@@ -141,6 +199,17 @@ impl Enemy {
 
   pub fn ai_take_turn(&mut self, tcod: &Tcod, game: &mut Game, other_enemies: &[Enemy], player: &mut Player) {
     use Ai::*;
+    if self.poisoned_turns > 0 {
+      self.poisoned_turns -= 1;
+      let damage = self.resisted_damage(POISON_DAMAGE, DamageType::Poison);
+      if damage > 0 {
+        game.messages.add(format!("The {} suffers from the poison.", self.get_name()), tcod::colors::DARK_GREEN);
+      }
+      if let Some(xp) = self.take_damage(damage, game) {
+        player.gain_xp(xp);
+        return;
+      }
+    }
     if let Some(ai) = self.ai.take() {
       let new_ai = match ai {
         Basic => self.ai_basic(tcod, game, other_enemies, player),
@@ -148,6 +217,8 @@ impl Enemy {
             previous_ai,
             num_turns,
         } => self.ai_confused(tcod, game, other_enemies, previous_ai, num_turns),
+        // shopkeepers wait behind their counter
+        Shopkeeper => Shopkeeper,
       };
       self.ai = Some(new_ai);
     }
@@ -176,6 +247,29 @@ impl Enemy {
       }
   }
 
+  /// replace the AI with a "confused" one; after some turns it restores the old AI
+  pub fn confuse(&mut self, num_turns: i32) {
+    let old_ai = self.ai.take().unwrap_or(Ai::Basic);
+    self.ai = Some(Ai::Confused {
+      previous_ai: Box::new(old_ai),
+      num_turns,
+    });
+  }
+
+  pub fn poison(&mut self, num_turns: i32) {
+    self.poisoned_turns = num_turns;
+  }
+
+  /// heal by the given amount, without going over the maximum
+  pub fn heal(&mut self, amount: i32) {
+    if let Some(ref mut fighter) = self.object.fighter {
+      fighter.hp += amount;
+      if fighter.hp > fighter.max_hp {
+        fighter.hp = fighter.max_hp;
+      }
+    }
+  }
+
   pub fn draw(&self,  con: &mut dyn Console) {
     self.object.draw(con)
   }
@@ -209,18 +303,42 @@ impl Enemy {
         );
         
         game.messages.add(format!("{} is dead!", self.object.get_name()), tcod::colors::ORANGE);
-        self.object.name = format!("remains of {}", self.object.get_name());
         
         return Some(fighter.xp);
       }
     }
+    if self.is_shopkeeper() && damage > 0 {
+      game.messages.add(format!("The {} gets angry!", self.get_name()), tcod::colors::RED);
+      self.ai = Some(Ai::Basic);
+    }
     None
   }
 
+  pub fn is_shopkeeper(&self) -> bool {
+    self.ai == Some(Ai::Shopkeeper)
+  }
+
+  pub fn get_stock(&self) -> &[Object] {
+    &self.stock
+  }
+
+  pub fn get_stock_mut(&mut self) -> &mut Vec<Object> {
+    &mut self.stock
+  }
+
+  /// return the damage actually taken once resistances are applied
+  pub fn resisted_damage(&self, damage: i32, damage_type: DamageType) -> i32 {
+    self.get_fighter().map_or(damage, |f| f.resistances.apply(damage, damage_type))
+  }
+
   pub fn pos(&self) -> (i32, i32) {
     (self.object.x, self.object.y)
   }
 
+  pub fn set_pos(&mut self, x: i32, y: i32) {
+    self.object.set_pos(x, y);
+  }
+
    pub fn get_x(&self) -> i32 {
     self.object.x
   }
@@ -245,12 +363,25 @@ impl Enemy {
     self.ai.clone()
   }
 
-  pub fn set_ai(&mut self, new_ai: Option<Ai>) {
-    self.ai = new_ai;
-  }
-
   pub fn distance(&self, x: i32, y: i32) -> f32 {
     self.object.distance(x, y)
   }
 
+}
+
+/// dead monsters leave an edible corpse behind, along with what they carried
+pub fn leave_corpses(enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, level: u32) {
+  for enemy in enemies.iter_mut().filter(|enemy| !enemy.object.alive) {
+    let (x, y) = enemy.pos();
+    collectibles.push(Object::create_corpse(x, y, &enemy.get_name()));
+    if rand::random::<f32>() < MONSTER_GOLD_CHANCE {
+      let amount = rand::thread_rng().gen_range(level as i32 * 3, level as i32 * 10 + 1);
+      place_on_floor(Object::create_gold(x, y, amount), collectibles);
+    }
+    for mut item in enemy.stock.drain(..) {
+      item.set_pos(x, y);
+      place_on_floor(item, collectibles);
+    }
+  }
+  enemies.retain(|enemy| enemy.object.alive);
 }
\ No newline at end of file
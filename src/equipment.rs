@@ -1,11 +1,121 @@
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use tcod::colors::Color;
+use crate::damage::Resistances;
+use crate::constants::CURSE_PENALTY;
+use crate::constants::BLESSING_BONUS;
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 /// An object that can be equipped, yielding bonuses.
 pub struct Equipment {
   pub slot: Slot,
   pub equipped: bool,
+  pub resistances: Resistances,
+  pub ranged: Option<RangedWeapon>,
+  pub power_bonus: i32,
+  pub defense_bonus: i32,
+  pub blessing: Blessing,
+  // whether the player knows if the item is cursed or blessed
+  pub blessing_known: bool,
+  pub rarity: Rarity,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Rarity {
+  Common,
+  Magic,
+  Rare,
+  Legendary,
+}
+
+impl Rarity {
+  pub fn color(self) -> Color {
+    match self {
+      Rarity::Common => tcod::colors::WHITE,
+      Rarity::Magic => tcod::colors::LIGHT_BLUE,
+      Rarity::Rare => tcod::colors::YELLOW,
+      Rarity::Legendary => tcod::colors::ORANGE,
+    }
+  }
+
+  pub fn price_multiplier(self) -> i32 {
+    match self {
+      Rarity::Common => 1,
+      Rarity::Magic => 2,
+      Rarity::Rare => 3,
+      Rarity::Legendary => 5,
+    }
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Blessing {
+  Cursed,
+  Uncursed,
+  Blessed,
+}
+
+impl Equipment {
+  pub fn is_cursed(&self) -> bool {
+    self.blessing == Blessing::Cursed
+  }
+
+  /// weapons lose attack and armors lose defense
+  pub fn curse(&mut self) {
+    self.blessing = Blessing::Cursed;
+    match self.slot {
+      Slot::LeftHand | Slot::RightHand => self.power_bonus -= CURSE_PENALTY,
+      Slot::Head => self.defense_bonus -= CURSE_PENALTY,
+    }
+  }
+
+  pub fn uncurse(&mut self) {
+    if self.is_cursed() {
+      self.blessing = Blessing::Uncursed;
+      match self.slot {
+        Slot::LeftHand | Slot::RightHand => self.power_bonus += CURSE_PENALTY,
+        Slot::Head => self.defense_bonus += CURSE_PENALTY,
+      }
+    }
+  }
+
+  pub fn bless(&mut self) {
+    self.blessing = Blessing::Blessed;
+    match self.slot {
+      Slot::LeftHand | Slot::RightHand => self.power_bonus += BLESSING_BONUS,
+      Slot::Head => self.defense_bonus += BLESSING_BONUS,
+    }
+  }
+
+  /// what the player can tell of the bonuses: a curse or blessing doesn't show until it is known
+  pub fn apparent(&self) -> Equipment {
+    let mut apparent = *self;
+    if !self.blessing_known {
+      match (self.blessing, self.slot) {
+        (Blessing::Cursed, Slot::LeftHand) | (Blessing::Cursed, Slot::RightHand) => apparent.power_bonus += CURSE_PENALTY,
+        (Blessing::Cursed, Slot::Head) => apparent.defense_bonus += CURSE_PENALTY,
+        (Blessing::Blessed, Slot::LeftHand) | (Blessing::Blessed, Slot::RightHand) => apparent.power_bonus -= BLESSING_BONUS,
+        (Blessing::Blessed, Slot::Head) => apparent.defense_bonus -= BLESSING_BONUS,
+        (Blessing::Uncursed, _) => {}
+      }
+    }
+    apparent
+  }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// A weapon shooting ammunition instead of hitting in melee.
+pub struct RangedWeapon {
+  pub ammo: Ammo,
+  pub range: i32,
+  pub damage: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Ammo {
+  Arrow,
+  Bolt,
+  Stone,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
@@ -25,6 +135,37 @@ impl fmt::Display for Slot {
   }
 }
 
+impl fmt::Display for Ammo {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Ammo::Arrow => write!(f, "arrow"),
+      Ammo::Bolt => write!(f, "bolt"),
+      Ammo::Stone => write!(f, "stone")
+    }
+  }
+}
+
+impl fmt::Display for Rarity {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Rarity::Common => write!(f, "common"),
+      Rarity::Magic => write!(f, "magic"),
+      Rarity::Rare => write!(f, "rare"),
+      Rarity::Legendary => write!(f, "legendary")
+    }
+  }
+}
+
+impl fmt::Display for Blessing {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Blessing::Cursed => write!(f, "cursed"),
+      Blessing::Uncursed => write!(f, "uncursed"),
+      Blessing::Blessed => write!(f, "blessed")
+    }
+  }
+}
+
 impl fmt::Display for Equipment {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     write!(f, "({}, {})", self.slot, self.equipped)
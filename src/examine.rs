@@ -0,0 +1,102 @@
+use crate::damage::DAMAGE_TYPES;
+use crate::effect::item_effect;
+use crate::equipment::Equipment;
+use crate::identification::Identification;
+use crate::object::Object;
+
+/// full description of an inventory item, compared with what is equipped in the same slot
+pub fn describe(item: &Object, inventory: &[Object], identification: &Identification) -> String {
+  let mut lines = vec![item.display_name(identification), String::new()];
+
+  let kind = match item.item {
+    Some(kind) => kind,
+    None => return lines.join("\n"),
+  };
+  if !identification.is_identified(kind) {
+    lines.push("You don't know what this does yet.".to_string());
+    return lines.join("\n");
+  }
+  lines.push(kind.description().to_string());
+
+  if let Some(item_effect) = item_effect(kind) {
+    lines.push(item_effect.summary());
+  }
+  if let Some(charges) = item.charges {
+    lines.push(format!("Charges: {}", charges));
+  }
+
+  if let Some(equipment) = item.equipment.map(|e| e.apparent()) {
+    lines.push(format!("Slot: {}", equipment.slot));
+    lines.push(format!("Rarity: {}", equipment.rarity));
+    if equipment.blessing_known {
+      lines.push(format!("It is {}.", equipment.blessing));
+    }
+    lines.extend(bonuses(&equipment));
+
+    let equipped = inventory.iter().filter_map(|other| other.equipment.map(|e| (other, e.apparent()))).find(|(_, e)| {
+      e.equipped && e.slot == equipment.slot
+    });
+    match equipped {
+      _ if equipment.equipped => lines.push("You are using it.".to_string()),
+      Some((other, other_equipment)) => {
+        lines.push(String::new());
+        lines.push(format!("Compared to your {}:", other.known_name(identification)));
+        let differences = differences(&equipment, &other_equipment);
+        if differences.is_empty() {
+          lines.push("No difference.".to_string());
+        }
+        lines.extend(differences);
+      }
+      None => lines.push(format!("Nothing is equipped in your {} slot.", equipment.slot.to_string().to_lowercase())),
+    }
+  }
+
+  lines.push(String::new());
+  lines.push(format!("Weight: {}", item.weight()));
+  lines.push(format!("Value: {} gold", item.apparent_price()));
+  lines.join("\n")
+}
+
+fn bonuses(equipment: &Equipment) -> Vec<String> {
+  let mut lines = vec![];
+  if equipment.power_bonus != 0 {
+    lines.push(format!("Attack: {:+}", equipment.power_bonus));
+  }
+  if equipment.defense_bonus != 0 {
+    lines.push(format!("Defense: {:+}", equipment.defense_bonus));
+  }
+  for damage_type in DAMAGE_TYPES {
+    let resistance = equipment.resistances.get(*damage_type);
+    if resistance != 0 {
+      lines.push(format!("{} resistance: {:+}%", damage_type, resistance));
+    }
+  }
+  if let Some(ranged) = equipment.ranged {
+    lines.push(format!("Shoots {}s: {} damage, range {}", ranged.ammo, ranged.damage, ranged.range));
+  }
+  lines
+}
+
+fn differences(equipment: &Equipment, other: &Equipment) -> Vec<String> {
+  let mut lines = vec![];
+  let power = equipment.power_bonus - other.power_bonus;
+  if power != 0 {
+    lines.push(format!("Attack: {:+}", power));
+  }
+  let defense = equipment.defense_bonus - other.defense_bonus;
+  if defense != 0 {
+    lines.push(format!("Defense: {:+}", defense));
+  }
+  for damage_type in DAMAGE_TYPES {
+    let resistance = equipment.resistances.get(*damage_type) - other.resistances.get(*damage_type);
+    if resistance != 0 {
+      lines.push(format!("{} resistance: {:+}%", damage_type, resistance));
+    }
+  }
+  let ranged_damage = |e: &Equipment| e.ranged.map_or(0, |ranged| ranged.damage);
+  let damage = ranged_damage(equipment) - ranged_damage(other);
+  if damage != 0 {
+    lines.push(format!("Ranged damage: {:+}", damage));
+  }
+  lines
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use crate::damage::{DamageType, Resistances};
 // combat-related properties and methods (monster, player, NPC).
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Fighter {
@@ -7,6 +8,8 @@ pub struct Fighter {
   pub defense: i32,
   pub power: i32,
   pub xp: i32,
+  pub damage_type: DamageType,
+  pub resistances: Resistances,
 }
 
 impl Fighter {
@@ -1,13 +1,20 @@
 use crate::transition::Transition;
 use crate::transition::from_dungeon_level;
 use crate::Tcod;
+use crate::activity::Activity;
 use serde::{Deserialize, Serialize};
 use crate::enemy::Enemy;
+use crate::identification::Identification;
+use crate::loot::LootGenerator;
+use crate::equipment::Ammo;
 use crate::object::is_blocked;
 use crate::object::Object;
 use crate::object::Item;
+use crate::pickup::PickupCategory;
 use crate::player::Player;
+use crate::class::Class;
 use std::cmp;
+use std::collections::VecDeque;
 use rand::Rng;
 use crate::constants::*;
 use crate::messages::Messages;
@@ -26,15 +33,38 @@ pub struct Game {
   pub messages: Messages,
   pub inventory: Vec<Object>,
   pub dungeon_level: u32,
+  /// the seed the loot of the current level was rolled from
+  pub loot_seed: usize,
+  pub identification: Identification,
+  pub auto_pickup: Vec<PickupCategory>,
+  /// what the player keeps doing on their own, if anything
+  pub activity: Option<Activity>,
+  /// turns the player has taken since the start of the game
+  pub turns: u32,
 }
 
 impl Game {
   pub fn new(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object> ) -> Self {
+    let loot_seed = rand::random();
     Game { 
-      map: make_map(player, enemies, collectibles, 1),
+      map: make_map(player, enemies, collectibles, 1, loot_seed),
       messages: Messages::new(),
       inventory: vec![],
       dungeon_level: 1,
+      loot_seed,
+      identification: Identification::new(),
+      auto_pickup: vec![PickupCategory::Gold],
+      activity: None,
+      turns: 0,
+    }
+  }
+
+  /// take `count` items from an inventory stack, removing the entry once it's empty
+  pub fn take_from_inventory(&mut self, inventory_id: usize, count: i32) -> Object {
+    if count >= self.inventory[inventory_id].quantity {
+      self.inventory.remove(inventory_id)
+    } else {
+      self.inventory[inventory_id].split(count)
     }
   }
 }
@@ -56,13 +86,39 @@ pub fn initialise_fov(tcod: &mut Tcod, map: &Map) {
   tcod.con.clear();
 }
 
-pub fn new_game(tcod: &mut Tcod) -> (Game, Player, Vec<Enemy>, Vec<Object>) {
+/// remember every tile the player can currently see
+pub fn explore_fov(tcod: &Tcod, game: &mut Game) {
+  for y in 0..MAP_HEIGHT {
+    for x in 0..MAP_WIDTH {
+      if tcod.fov.is_in_fov(x, y) {
+        game.map[x as usize][y as usize].explore();
+      }
+    }
+  }
+}
+
+pub fn new_game(tcod: &mut Tcod, class: Class) -> (Game, Player, Vec<Enemy>, Vec<Object>) {
   // create object representing the player
-  let mut player = Player::new(0, 0);
+  let mut player = Player::new(0, 0, class);
   let mut enemies = vec![];
   let mut collectibles = vec![];
   let mut game = Game::new(&mut player, &mut enemies, &mut collectibles);
 
+  for known in class.known_items() {
+    game.identification.identify(&known);
+  }
+  // the starting kit is known and ready to use
+  for mut item in class.starting_items() {
+    game.identification.identify(&item);
+    if let Some(ref mut equipment) = item.equipment {
+      equipment.blessing_known = true;
+      if game.inventory.iter().all(|other| other.equipment.map_or(true, |e| !e.equipped || e.slot != equipment.slot)) {
+        equipment.equipped = true;
+      }
+    }
+    game.inventory.push(item);
+  }
+
   // a warm welcoming message!
   game.messages.add(
     "Welcome stranger! Prepare to perish in the Tombs of the Ancient Kings.",
@@ -87,15 +143,17 @@ pub fn next_level(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies
         tcod::colors::RED,
     );
     game.dungeon_level += 1;
-    game.map = make_map(player, enemies, collectibles, game.dungeon_level);
+    game.loot_seed = rand::random();
+    game.map = make_map(player, enemies, collectibles, game.dungeon_level, game.loot_seed);
     initialise_fov(tcod, &game.map);
 }
 
-fn make_map(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, level: u32) -> Map {
+fn make_map(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, level: u32, loot_seed: usize) -> Map {
   collectibles.clear();
   enemies.clear();
   let mut rooms = vec![];
   let mut map = vec![vec![Tile::wall(); MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+  let mut loot = LootGenerator::new(loot_seed);
 
   for _ in 0..MAX_ROOMS {
     // random width and height
@@ -118,7 +176,7 @@ fn make_map(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Ve
       // "paint" it to the map's tiles
       create_room(new_room, &mut map);
 
-      place_objects(new_room, enemies, collectibles, &mut map, level);
+      place_objects(new_room, enemies, collectibles, &mut map, level, &mut loot);
 
       // center coordinates of the new room, will be useful later
       let (new_x, new_y) = new_room.center();
@@ -144,6 +202,20 @@ fn make_map(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Ve
     rooms.push(new_room);
     }
   }
+  // a room between the first and the last one may be turned into a shop
+  if level >= 2 && rooms.len() > 2 && rand::random::<f32>() < SHOP_CHANCE {
+    let shop_room = rooms[rand::thread_rng().gen_range(1, rooms.len() - 1)];
+    create_shop(shop_room, enemies, collectibles, level, &mut loot);
+  }
+
+  // sometimes a locked vault is hidden in the rock, its key lies somewhere on the level
+  if rand::random::<f32>() < VAULT_CHANCE {
+    if let Some(vault) = create_vault(&rooms, &mut map) {
+      fill_vault(vault, collectibles, level, &mut loot);
+      place_key(player.pos(), &map, enemies, collectibles);
+    }
+  }
+
   // create stairs at the center of the last room
   let (last_room_x, last_room_y) = rooms[rooms.len() - 1].center();
   let stairs = Object::create_stair(last_room_x, last_room_y);
@@ -152,6 +224,123 @@ fn make_map(player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Ve
   map
 }
 
+fn create_shop(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, level: u32, loot: &mut LootGenerator) {
+  // the shopkeeper keeps their room clean
+  enemies.retain(|enemy| !room.contains(enemy.get_x(), enemy.get_y()));
+  collectibles.retain(|item| !room.contains(item.x, item.y));
+
+  let (x, y) = room.center();
+  let mut stock = vec![];
+  for _ in 0..rand::thread_rng().gen_range(2, 5) {
+    stock.push(loot.generate(x, y, level));
+  }
+  let mut potions = Object::create_potion(x, y);
+  potions.quantity = rand::thread_rng().gen_range(2, 5);
+  stock.push(potions);
+  let mut rations = Object::create_ration(x, y);
+  rations.quantity = rand::thread_rng().gen_range(1, 4);
+  stock.push(rations);
+  stock.push(Object::create_identify_scroll(x, y));
+  stock.push(Object::create_ammo(x, y, Ammo::Arrow, 20));
+  enemies.push(Enemy::create_shopkeeper(x, y, stock));
+}
+
+/// carve a vault in solid rock, its only entrance is a locked door leading to the nearest room
+fn create_vault(rooms: &[Rect], map: &mut Map) -> Option<Rect> {
+  for _ in 0..VAULT_ATTEMPTS {
+    let w = rand::thread_rng().gen_range(VAULT_MIN_SIZE, VAULT_MAX_SIZE + 1);
+    let h = rand::thread_rng().gen_range(VAULT_MIN_SIZE, VAULT_MAX_SIZE + 1);
+    let x = rand::thread_rng().gen_range(1, MAP_WIDTH - w - 1);
+    let y = rand::thread_rng().gen_range(1, MAP_HEIGHT - h - 1);
+    let vault = Rect::new(x, y, w, h);
+
+    // keep a margin of rock around the vault so that no tunnel touches its walls
+    let solid = ((x - 1)..=(x + w + 1))
+      .all(|tile_x| ((y - 1)..=(y + h + 1)).all(|tile_y| map[tile_x as usize][tile_y as usize].is_blocked()));
+    if !solid {
+      continue;
+    }
+
+    let (x, y) = vault.center();
+    let (room_x, room_y) = rooms
+      .iter()
+      .map(|room| room.center())
+      .min_by_key(|&(room_x, room_y)| (room_x - x).pow(2) + (room_y - y).pow(2))?;
+    create_room(vault, map);
+
+    // dig away from the vault first, so that the tunnel never crosses it
+    if room_y < vault.y1() || room_y > vault.y2() {
+      let (door_y, outside_y) = if room_y < vault.y1() {
+        (vault.y1(), vault.y1() - 1)
+      } else {
+        (vault.y2(), vault.y2() + 1)
+      };
+      create_v_tunnel(outside_y, room_y, x, map);
+      create_h_tunnel(x, room_x, room_y, map);
+      map[x as usize][door_y as usize] = Tile::locked_door();
+    } else {
+      let (door_x, outside_x) = if room_x < vault.x1() {
+        (vault.x1(), vault.x1() - 1)
+      } else {
+        (vault.x2(), vault.x2() + 1)
+      };
+      create_h_tunnel(outside_x, room_x, y, map);
+      create_v_tunnel(y, room_y, room_x, map);
+      map[door_x as usize][y as usize] = Tile::locked_door();
+    }
+    return Some(vault);
+  }
+  None
+}
+
+fn fill_vault(vault: Rect, collectibles: &mut Vec<Object>, level: u32, loot: &mut LootGenerator) {
+  let (x, y) = vault.center();
+  let amount = rand::thread_rng().gen_range(level as i32 * 20, level as i32 * 50 + 1);
+  collectibles.push(Object::create_gold(x, y, amount));
+  for _ in 0..rand::thread_rng().gen_range(2, 5) {
+    let x = rand::thread_rng().gen_range(vault.x1() + 1, vault.x2());
+    let y = rand::thread_rng().gen_range(vault.y1() + 1, vault.y2());
+    collectibles.push(loot.generate(x, y, level + VAULT_LOOT_BONUS));
+  }
+}
+
+/// drop the vault key on a free tile the player can walk to without opening any door
+fn place_key(start: (i32, i32), map: &Map, enemies: &[Enemy], collectibles: &mut Vec<Object>) {
+  let candidates = reachable_tiles(start, map)
+    .into_iter()
+    .filter(|&pos| {
+      pos != start && !enemies.iter().any(|enemy| enemy.pos() == pos) && !collectibles.iter().any(|item| item.pos() == pos)
+    })
+    .collect::<Vec<_>>();
+  if let Some(&(x, y)) = rand::thread_rng().choose(&candidates) {
+    collectibles.push(Object::create_key(x, y));
+  }
+}
+
+/// every tile that can be walked to from the start without opening a door, the start included
+pub fn reachable_tiles(start: (i32, i32), map: &Map) -> Vec<(i32, i32)> {
+  let mut reachable = vec![vec![false; MAP_HEIGHT as usize]; MAP_WIDTH as usize];
+  let mut queue = VecDeque::new();
+  let mut tiles = vec![];
+  reachable[start.0 as usize][start.1 as usize] = true;
+  queue.push_back(start);
+  while let Some((x, y)) = queue.pop_front() {
+    tiles.push((x, y));
+    for &(dx, dy) in &[(-1, 0), (1, 0), (0, -1), (0, 1)] {
+      let (next_x, next_y) = (x + dx, y + dy);
+      if next_x < 0 || next_y < 0 || next_x >= MAP_WIDTH || next_y >= MAP_HEIGHT {
+        continue;
+      }
+      let (tile_x, tile_y) = (next_x as usize, next_y as usize);
+      if !reachable[tile_x][tile_y] && !map[tile_x][tile_y].is_blocked() {
+        reachable[tile_x][tile_y] = true;
+        queue.push_back((next_x, next_y));
+      }
+    }
+  }
+  tiles
+}
+
 fn create_room(room: Rect, map: &mut Map) {
   // go through the tiles in the rectangle and make them passable
   for x in (room.x1() + 1)..room.x2() {
@@ -175,7 +364,7 @@ fn create_v_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
   }
 }
 
-fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, map: &Map, level: u32) {
+fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>, map: &Map, level: u32, loot: &mut LootGenerator) {
   let max_monsters = from_dungeon_level(
     &[
       Transition { level: 1, value: 2 },
@@ -207,6 +396,20 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
     level,
   );
 
+  let fire_elemental_chance = from_dungeon_level(
+    &[
+      Transition {
+          level: 4,
+          value: 10,
+      },
+      Transition {
+          level: 6,
+          value: 20,
+      },
+    ],
+    level,
+  );
+
   
   //clone => dirty 
   let object_enemies = enemies
@@ -231,6 +434,10 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
             weight: troll_chance,
             item: "troll",
         },
+        Weighted {
+            weight: fire_elemental_chance,
+            item: "fire elemental",
+        },
     ];
     let monster_choice = WeightedChoice::new(monster_chances);
 
@@ -244,6 +451,9 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
               // create a troll
               Enemy::create_troll(x, y)
           }
+          "fire elemental" => {
+              Enemy::create_fire_elemental(x, y)
+          }
           _ => unreachable!(),
       };
       enemies.push(monster);
@@ -265,7 +475,7 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
   let item_chances = &mut [
       Weighted {
           weight: 70,
-          item: Item::Heal,
+          item: "heal",
       },
       Weighted {
           weight: from_dungeon_level(
@@ -275,7 +485,7 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
             }],
             level)
           ,
-          item: Item::Lightning,
+          item: "lightning",
       },
       Weighted {
           weight: from_dungeon_level(
@@ -285,7 +495,81 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
             }],
             level)
           ,
-          item: Item::Fireball,
+          item: "fireball",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "confuse",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "confusion potion",
+      },
+      Weighted {
+          weight: 15,
+          item: "identify",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 3,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "recharge",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 3,
+              value: 5,
+            }],
+            level)
+          ,
+          item: "lightning wand",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 3,
+              value: 5,
+            }],
+            level)
+          ,
+          item: "confusion wand",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 6,
+              value: 5,
+            }],
+            level)
+          ,
+          item: "fireball wand",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 5,
+            }],
+            level)
+          ,
+          item: "digging wand",
       },
       Weighted {
           weight: from_dungeon_level(
@@ -295,12 +579,102 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
             }],
             level)
           ,
-          item: Item::Confuse,
+          item: "remove curse",
+      },
+      Weighted {
+          weight: 20,
+          item: "ration",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "poison potion",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 4,
+              value: 5,
+            }],
+            level)
+          ,
+          item: "mass confusion",
+      },
+      Weighted {
+          weight: 10,
+          item: "teleport",
+      },
+      Weighted {
+          weight: 30,
+          item: "gold",
       },
       Weighted 
         {
           weight: 1000,
-          item: Item::Equipment
+          item: "equipment"
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 3,
+              value: 15,
+            }],
+            level)
+          ,
+          item: "fire helm",
+      },
+      Weighted {
+          weight: 10,
+          item: "sling",
+      },
+      Weighted {
+          weight: 15,
+          item: "stones",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "bow",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 2,
+              value: 15,
+            }],
+            level)
+          ,
+          item: "arrows",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 5,
+              value: 10,
+            }],
+            level)
+          ,
+          item: "crossbow",
+      },
+      Weighted {
+          weight: from_dungeon_level(
+            &[Transition {
+              level: 5,
+              value: 15,
+            }],
+            level)
+          ,
+          item: "bolts",
       },
   ];
   let item_choice = WeightedChoice::new(item_chances);
@@ -312,14 +686,48 @@ fn place_objects(room: Rect, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Ob
 
     // only place it if the tile is not blocked
     if !is_blocked(x, y, map, &concatenated_objects) {
-      let item = match item_choice.ind_sample(&mut rand::thread_rng()) {
-        Item::Heal => { Object::create_potion(x, y) }
-        Item::Lightning => { Object::create_lighting_bolt(x, y) }
-        Item::Fireball => { Object::create_fireball(x, y) }
-        Item::Confuse => { Object::create_confuse_potion(x, y) }
-        Item::Equipment => { Object::create_sword(x, y) }
+      let mut item = match item_choice.ind_sample(&mut rand::thread_rng()) {
+        "heal" => { Object::create_potion(x, y) }
+        "lightning" => { Object::create_lighting_bolt(x, y) }
+        "fireball" => { Object::create_fireball(x, y) }
+        "confuse" => { Object::create_confuse_potion(x, y) }
+        "confusion potion" => { Object::create_confusion_potion(x, y) }
+        "identify" => { Object::create_identify_scroll(x, y) }
+        "recharge" => { Object::create_recharge_scroll(x, y) }
+        "remove curse" => { Object::create_remove_curse_scroll(x, y) }
+        "ration" => { Object::create_ration(x, y) }
+        "poison potion" => { Object::create_potion_of(x, y, Item::PoisonPotion, "potion of poison", tcod::colors::VIOLET) }
+        "mass confusion" => { Object::create_scroll(x, y, Item::MassConfusion, "scroll of mass confusion") }
+        "teleport" => { Object::create_scroll(x, y, Item::Teleport, "scroll of teleportation") }
+        "gold" => { Object::create_gold(x, y, rand::thread_rng().gen_range(level as i32 * 5, level as i32 * 20 + 1)) }
+        "lightning wand" => { Object::create_wand(x, y, Item::LightningWand, "wand of lightning") }
+        "confusion wand" => { Object::create_wand(x, y, Item::ConfusionWand, "wand of confusion") }
+        "fireball wand" => { Object::create_wand(x, y, Item::FireballWand, "wand of fireball") }
+        "digging wand" => { Object::create_wand(x, y, Item::DiggingWand, "wand of digging") }
+        "equipment" => { loot.generate(x, y, level) }
+        "fire helm" => { Object::create_fire_helm(x, y) }
+        "sling" => { Object::create_sling(x, y) }
+        "bow" => { Object::create_bow(x, y) }
+        "crossbow" => { Object::create_crossbow(x, y) }
+        "stones" => { Object::create_ammo(x, y, Ammo::Stone, rand::thread_rng().gen_range(5, 16)) }
+        "arrows" => { Object::create_ammo(x, y, Ammo::Arrow, rand::thread_rng().gen_range(5, 16)) }
+        "bolts" => { Object::create_ammo(x, y, Ammo::Bolt, rand::thread_rng().gen_range(5, 16)) }
+        _ => unreachable!(),
       };
+      roll_blessing(&mut item);
       collectibles.push(item);
     }
   }
+}
+
+// equipment found in the dungeon is sometimes cursed or blessed
+fn roll_blessing(object: &mut Object) {
+  if let Some(ref mut equipment) = object.equipment {
+    let roll = rand::random::<f32>();
+    if roll < CURSE_CHANCE {
+      equipment.curse();
+    } else if roll < CURSE_CHANCE + BLESSING_CHANCE {
+      equipment.bless();
+    }
+  }
 }
\ No newline at end of file
@@ -1,6 +1,8 @@
+use crate::identification::Identification;
 use crate::object::Object;
 use crate::constants::*;
 use crate::game::Game;
+use crate::hunger::Hunger;
 use crate::player::Player;
 use tcod::console::*;
 use tcod::colors::Color;
@@ -75,6 +77,20 @@ pub fn render_gui(tcod: &mut Tcod, game: &Game, player: &Player, enemys: &[Enemy
     tcod::colors::DARKER_RED,
   );
 
+  if player.get_max_mana() > 0 {
+    render_bar(
+      &mut tcod.panel,
+      1,
+      2,
+      BAR_WIDTH,
+      "Mana",
+      player.get_mana(),
+      player.get_max_mana(),
+      tcod::colors::LIGHT_BLUE,
+      tcod::colors::DARKER_BLUE,
+    );
+  }
+
   tcod.panel.print_ex(
     1,
     3,
@@ -83,6 +99,38 @@ pub fn render_gui(tcod: &mut Tcod, game: &Game, player: &Player, enemys: &[Enemy
     format!("Dungeon level: {}", game.dungeon_level),
   );
 
+  let hunger = player.hunger();
+  if hunger != Hunger::Satiated {
+    tcod.panel.set_default_foreground(hunger.color());
+    tcod.panel.print_ex(
+      1,
+      4,
+      BackgroundFlag::None,
+      TextAlignment::Left,
+      hunger.to_string(),
+    );
+  }
+
+  if player.is_burdened(game) {
+    tcod.panel.set_default_foreground(tcod::colors::ORANGE);
+    tcod.panel.print_ex(
+      12,
+      4,
+      BackgroundFlag::None,
+      TextAlignment::Left,
+      "Burdened",
+    );
+  }
+
+  tcod.panel.set_default_foreground(tcod::colors::GOLD);
+  tcod.panel.print_ex(
+    1,
+    5,
+    BackgroundFlag::None,
+    TextAlignment::Left,
+    format!("Gold: {}", player.get_gold()),
+  );
+
   render_messages(tcod, game);
   render_raycast(tcod, enemys);
 
@@ -124,15 +172,19 @@ fn render_raycast(tcod: &mut Tcod, enemys: &[Enemy]) {
 }
 
 
-pub fn inventory_menu(inventory: &[Object], header: &str, root: &mut Root) -> Option<usize> {
+pub fn inventory_menu(inventory: &[Object], identification: &Identification, header: &str, root: &mut Root) -> Option<usize> {
     // how a menu with each item of the inventory as an option
+    // each item is shown in the color of its rarity
     let options = if inventory.len() == 0 {
-        vec!["Inventory is empty.".into()]
+        vec![("Inventory is empty.".into(), tcod::colors::WHITE)]
     } else {
-        inventory.iter().map(|item| item.name.clone()).collect()
+        inventory
+          .iter()
+          .map(|item| (item.display_name(identification), item.rarity().color()))
+          .collect()
     };
 
-    let inventory_index = menu(header, &options, INVENTORY_WIDTH, root);
+    let inventory_index = colored_menu(header, &options, INVENTORY_WIDTH, root);
 
     // if an item was chosen, return it
     if inventory.len() > 0 {
@@ -144,6 +196,14 @@ pub fn inventory_menu(inventory: &[Object], header: &str, root: &mut Root) -> Op
 
 
 pub fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, root: &mut Root) -> Option<usize> {
+  let options = options
+    .iter()
+    .map(|option| (option.as_ref(), tcod::colors::WHITE))
+    .collect::<Vec<_>>();
+  colored_menu(header, &options, width, root)
+}
+
+pub fn colored_menu<T: AsRef<str>>(header: &str, options: &[(T, Color)], width: i32, root: &mut Root) -> Option<usize> {
   assert!(
     options.len() <= MAX_INVENTORY,
     format!("Cannot have a menu with more than {} options.", MAX_INVENTORY)
@@ -174,9 +234,10 @@ pub fn menu<T: AsRef<str>>(header: &str, options: &[T], width: i32, root: &mut R
   );
   
   // print all the options
-  for (index, option_text) in options.iter().enumerate() {
+  for (index, (option_text, color)) in options.iter().enumerate() {
     let menu_letter = (b'a' + index as u8) as char;
     let text = format!("({}) {}", menu_letter, option_text.as_ref());
+    window.set_default_foreground(*color);
     window.print_ex(
         0,
         header_height + index as i32,
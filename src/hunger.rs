@@ -0,0 +1,46 @@
+use crate::constants::HUNGRY_NUTRITION;
+use crate::constants::WEAK_NUTRITION;
+use std::fmt;
+use tcod::colors::Color;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hunger {
+  Satiated,
+  Hungry,
+  Weak,
+  Starving,
+}
+
+impl Hunger {
+  pub fn from_nutrition(nutrition: i32) -> Self {
+    if nutrition <= 0 {
+      Hunger::Starving
+    } else if nutrition < WEAK_NUTRITION {
+      Hunger::Weak
+    } else if nutrition < HUNGRY_NUTRITION {
+      Hunger::Hungry
+    } else {
+      Hunger::Satiated
+    }
+  }
+
+  pub fn color(self) -> Color {
+    match self {
+      Hunger::Satiated => tcod::colors::WHITE,
+      Hunger::Hungry => tcod::colors::YELLOW,
+      Hunger::Weak => tcod::colors::ORANGE,
+      Hunger::Starving => tcod::colors::RED,
+    }
+  }
+}
+
+impl fmt::Display for Hunger {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      Hunger::Satiated => write!(f, "Not hungry"),
+      Hunger::Hungry => write!(f, "Hungry"),
+      Hunger::Weak => write!(f, "Weak"),
+      Hunger::Starving => write!(f, "Starving")
+    }
+  }
+}
@@ -0,0 +1,98 @@
+use crate::object::Item;
+use crate::object::Object;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+// every kind of potion, scroll and wand gets a random appearance at the start of a game
+const POTIONS: &[Item] = &[Item::Heal, Item::ConfusionPotion, Item::PoisonPotion];
+const SCROLLS: &[Item] = &[Item::Lightning, Item::Confuse, Item::Fireball, Item::Identify, Item::Recharge, Item::RemoveCurse, Item::MassConfusion, Item::Teleport];
+const WANDS: &[Item] = &[Item::LightningWand, Item::ConfusionWand, Item::FireballWand, Item::DiggingWand];
+
+const POTION_LOOKS: &[&str] = &[
+  "murky", "bubbling", "smoky", "fizzy", "golden", "milky", "oily", "glowing", "cloudy", "swirling",
+];
+const WAND_MATERIALS: &[&str] = &[
+  "oak", "iron", "bone", "crystal", "copper", "ebony", "glass", "silver",
+];
+const SCROLL_SYLLABLES: &[&str] = &[
+  "xy", "zzy", "ab", "ra", "ka", "dab", "zel", "nor", "ek", "lo", "mar", "fu", "kir", "ven", "tho",
+];
+
+#[derive(Serialize, Deserialize)]
+/// Per-game mapping between item kinds and what they look like until identified.
+pub struct Identification {
+  appearances: Vec<(Item, String)>,
+  identified: Vec<(Item, String)>,
+}
+
+impl Identification {
+  pub fn new() -> Self {
+    let mut rng = rand::thread_rng();
+    let mut appearances = vec![];
+
+    let mut looks = POTION_LOOKS.to_vec();
+    rng.shuffle(&mut looks);
+    for (potion, look) in POTIONS.iter().zip(looks) {
+      appearances.push((*potion, format!("{} potion", look)));
+    }
+
+    let mut materials = WAND_MATERIALS.to_vec();
+    rng.shuffle(&mut materials);
+    for (wand, material) in WANDS.iter().zip(materials) {
+      appearances.push((*wand, format!("{} wand", material)));
+    }
+
+    let mut labels: Vec<String> = vec![];
+    for scroll in SCROLLS {
+      // keep drawing syllables until the label is unique
+      let label = loop {
+        let syllables = rng.gen_range(2, 4);
+        let label = (0..syllables)
+          .map(|_| *rng.choose(SCROLL_SYLLABLES).unwrap())
+          .collect::<String>()
+          .to_uppercase();
+        if !labels.contains(&label) {
+          break label;
+        }
+      };
+      appearances.push((*scroll, format!("scroll labeled {}", label)));
+      labels.push(label);
+    }
+
+    Identification {
+      appearances,
+      identified: vec![],
+    }
+  }
+
+  /// items without a random appearance are always known
+  pub fn is_identified(&self, item: Item) -> bool {
+    self.appearance(item).is_none() || self.identified.iter().any(|(known, _)| *known == item)
+  }
+
+  pub fn appearance(&self, item: Item) -> Option<&str> {
+    self.appearances
+      .iter()
+      .find(|(kind, _)| *kind == item)
+      .map(|(_, appearance)| appearance.as_str())
+  }
+
+  /// learn what an object is, returns false if it was already known
+  pub fn identify(&mut self, object: &Object) -> bool {
+    match object.item {
+      Some(item) if !self.is_identified(item) => {
+        self.identified.push((item, object.name.clone()));
+        true
+      }
+      _ => false,
+    }
+  }
+
+  /// every identified appearance with the real name behind it
+  pub fn discoveries(&self) -> Vec<String> {
+    self.identified
+      .iter()
+      .filter_map(|(item, name)| self.appearance(*item).map(|appearance| format!("{}: {}", appearance, name)))
+      .collect()
+  }
+}
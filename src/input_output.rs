@@ -3,7 +3,10 @@ use crate::game::Game;
 use crate::player::Player;
 use crate::enemy::Enemy;
 use crate::object::Object;
-use std::fs::File;
+use crate::keymap::{Action, Keymap};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::Path;
 use std::io::{Read, Write};
 
 
@@ -14,10 +17,30 @@ pub fn save_game(game: &Game, player: &Player, enemies: &[Enemy], collectibles:
   Ok(())
 }
 
+/// remove the saved game, a dead character can't be continued
+pub fn delete_save() -> Result<(), Box<dyn Error>> {
+  if Path::new("savegame").exists() {
+    fs::remove_file("savegame")?;
+  }
+  Ok(())
+}
+
 pub fn load_game() -> Result<(Game, Player, Vec<Enemy>, Vec<Object>), Box<dyn Error>> {
   let mut json_save_state = String::new();
   let mut file = File::open("savegame")?;
   file.read_to_string(&mut json_save_state)?;
   let result = serde_json::from_str::<(Game, Player, Vec<Enemy>, Vec<Object>)>(&json_save_state)?;
   Ok(result)
-}
\ No newline at end of file
+}
+
+/// read the key bindings from keymap.json, the defaults are used if there is no such file
+pub fn load_keymap() -> Result<Keymap, Box<dyn Error>> {
+  let mut json_keymap = String::new();
+  let mut file = match File::open("keymap.json") {
+    Ok(file) => file,
+    Err(_) => return Ok(Keymap::default()),
+  };
+  file.read_to_string(&mut json_keymap)?;
+  let keys = serde_json::from_str::<BTreeMap<Action, Vec<String>>>(&json_keymap)?;
+  Keymap::new(keys)
+}
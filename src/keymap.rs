@@ -0,0 +1,275 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use tcod::input::{Key, KeyCode};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+/// Everything the player can ask for from the map screen, whatever key it is bound to.
+pub enum Action {
+  MoveUp,
+  MoveDown,
+  MoveLeft,
+  MoveRight,
+  MoveUpLeft,
+  MoveUpRight,
+  MoveDownLeft,
+  MoveDownRight,
+  Wait,
+  Rest,
+  Explore,
+  TravelToStairs,
+  Descend,
+  PickUp,
+  AutoPickupOptions,
+  Inventory,
+  Examine,
+  Look,
+  Throw,
+  Drop,
+  Fire,
+  CastSpell,
+  ChoosePerk,
+  Character,
+  Discoveries,
+  Help,
+  Fullscreen,
+  Exit,
+}
+
+pub const ACTIONS: &[Action] = &[
+  Action::MoveUp,
+  Action::MoveDown,
+  Action::MoveLeft,
+  Action::MoveRight,
+  Action::MoveUpLeft,
+  Action::MoveUpRight,
+  Action::MoveDownLeft,
+  Action::MoveDownRight,
+  Action::Wait,
+  Action::Rest,
+  Action::Explore,
+  Action::TravelToStairs,
+  Action::Descend,
+  Action::PickUp,
+  Action::AutoPickupOptions,
+  Action::Inventory,
+  Action::Examine,
+  Action::Look,
+  Action::Throw,
+  Action::Drop,
+  Action::Fire,
+  Action::CastSpell,
+  Action::ChoosePerk,
+  Action::Character,
+  Action::Discoveries,
+  Action::Help,
+  Action::Fullscreen,
+  Action::Exit,
+];
+
+/// keys that don't type a character, by the name used in the keymap file
+const NAMED_KEYS: &[(&str, KeyCode)] = &[
+  ("Escape", KeyCode::Escape),
+  ("Enter", KeyCode::Enter),
+  ("Tab", KeyCode::Tab),
+  ("Backspace", KeyCode::Backspace),
+  ("Up", KeyCode::Up),
+  ("Down", KeyCode::Down),
+  ("Left", KeyCode::Left),
+  ("Right", KeyCode::Right),
+  ("Home", KeyCode::Home),
+  ("End", KeyCode::End),
+  ("PageUp", KeyCode::PageUp),
+  ("PageDown", KeyCode::PageDown),
+  ("Insert", KeyCode::Insert),
+  ("Delete", KeyCode::Delete),
+  ("NumPad0", KeyCode::NumPad0),
+  ("NumPad1", KeyCode::NumPad1),
+  ("NumPad2", KeyCode::NumPad2),
+  ("NumPad3", KeyCode::NumPad3),
+  ("NumPad4", KeyCode::NumPad4),
+  ("NumPad5", KeyCode::NumPad5),
+  ("NumPad6", KeyCode::NumPad6),
+  ("NumPad7", KeyCode::NumPad7),
+  ("NumPad8", KeyCode::NumPad8),
+  ("NumPad9", KeyCode::NumPad9),
+  ("NumPadEnter", KeyCode::NumPadEnter),
+  ("F1", KeyCode::F1),
+  ("F2", KeyCode::F2),
+  ("F3", KeyCode::F3),
+  ("F4", KeyCode::F4),
+  ("F5", KeyCode::F5),
+  ("F6", KeyCode::F6),
+  ("F7", KeyCode::F7),
+  ("F8", KeyCode::F8),
+  ("F9", KeyCode::F9),
+  ("F10", KeyCode::F10),
+  ("F11", KeyCode::F11),
+  ("F12", KeyCode::F12),
+];
+
+impl Action {
+  pub fn description(self) -> &'static str {
+    match self {
+      Action::MoveUp => "move or attack up",
+      Action::MoveDown => "move or attack down",
+      Action::MoveLeft => "move or attack left",
+      Action::MoveRight => "move or attack right",
+      Action::MoveUpLeft => "move or attack up-left",
+      Action::MoveUpRight => "move or attack up-right",
+      Action::MoveDownLeft => "move or attack down-left",
+      Action::MoveDownRight => "move or attack down-right",
+      Action::Wait => "wait a turn",
+      Action::Rest => "rest until healed",
+      Action::Explore => "explore the level",
+      Action::TravelToStairs => "travel to the stairs",
+      Action::Descend => "go down the stairs",
+      Action::PickUp => "pick up items",
+      Action::AutoPickupOptions => "auto-pickup options",
+      Action::Inventory => "use an item",
+      Action::Examine => "examine an item",
+      Action::Look => "look around",
+      Action::Throw => "throw an item",
+      Action::Drop => "drop an item",
+      Action::Fire => "fire the ranged weapon",
+      Action::CastSpell => "cast a spell",
+      Action::ChoosePerk => "choose a perk",
+      Action::Character => "character information",
+      Action::Discoveries => "discoveries",
+      Action::Help => "this help",
+      Action::Fullscreen => "toggle fullscreen",
+      Action::Exit => "save and quit",
+    }
+  }
+
+  /// the direction of a movement action
+  pub fn direction(self) -> Option<(i32, i32)> {
+    match self {
+      Action::MoveUp => Some((0, -1)),
+      Action::MoveDown => Some((0, 1)),
+      Action::MoveLeft => Some((-1, 0)),
+      Action::MoveRight => Some((1, 0)),
+      Action::MoveUpLeft => Some((-1, -1)),
+      Action::MoveUpRight => Some((1, -1)),
+      Action::MoveDownLeft => Some((-1, 1)),
+      Action::MoveDownRight => Some((1, 1)),
+      _ => None,
+    }
+  }
+
+  fn default_keys(self) -> &'static [&'static str] {
+    match self {
+      Action::MoveUp => &["Up", "NumPad8", "k"],
+      Action::MoveDown => &["Down", "NumPad2", "j"],
+      Action::MoveLeft => &["Left", "NumPad4", "h"],
+      Action::MoveRight => &["Right", "NumPad6", "l"],
+      Action::MoveUpLeft => &["Home", "NumPad7", "y"],
+      Action::MoveUpRight => &["PageUp", "NumPad9", "u"],
+      Action::MoveDownLeft => &["End", "NumPad1", "b"],
+      Action::MoveDownRight => &["PageDown", "NumPad3", "n"],
+      Action::Wait => &[".", "NumPad5"],
+      Action::Rest => &["R"],
+      Action::Explore => &["o"],
+      Action::TravelToStairs => &["G"],
+      Action::Descend => &[">"],
+      Action::PickUp => &["g", ","],
+      Action::AutoPickupOptions => &["O"],
+      Action::Inventory => &["i"],
+      Action::Examine => &["x"],
+      Action::Look => &[";"],
+      Action::Throw => &["t"],
+      Action::Drop => &["d"],
+      Action::Fire => &["f"],
+      Action::CastSpell => &["z"],
+      Action::ChoosePerk => &["p"],
+      Action::Character => &["c"],
+      Action::Discoveries => &["\\"],
+      Action::Help => &["?", "F1"],
+      Action::Fullscreen => &["Alt+Enter"],
+      Action::Exit => &["Escape"],
+    }
+  }
+}
+
+/// Which keys trigger which action.
+pub struct Keymap {
+  keys: BTreeMap<Action, Vec<String>>,
+  actions: HashMap<String, Action>,
+}
+
+impl Default for Keymap {
+  fn default() -> Self {
+    Keymap::new(BTreeMap::new()).unwrap()
+  }
+}
+
+impl Keymap {
+  /// bind the given keys, actions left out keep their default keys.
+  /// Fails on unknown key names and on keys bound to two actions.
+  pub fn new(mut keys: BTreeMap<Action, Vec<String>>) -> Result<Self, Box<dyn Error>> {
+    for action in ACTIONS {
+      keys
+        .entry(*action)
+        .or_insert_with(|| action.default_keys().iter().map(|key| key.to_string()).collect());
+    }
+    let mut actions = HashMap::new();
+    for (action, action_keys) in &keys {
+      for key in action_keys {
+        if !is_key_name(key) {
+          return Err(format!("Unknown key \"{}\" for {:?}.", key, action).into());
+        }
+        if let Some(other) = actions.insert(key.clone(), *action) {
+          return Err(format!("Key \"{}\" is bound to both {:?} and {:?}.", key, other, action).into());
+        }
+      }
+    }
+    Ok(Keymap { keys, actions })
+  }
+
+  /// the action bound to a key press, and whether shift was held on a movement key to run
+  pub fn action(&self, key: Key) -> Option<(Action, bool)> {
+    let name = key_name(key)?;
+    if let Some(action) = self.actions.get(&name) {
+      let run = key.code != KeyCode::Text && key.shift && action.direction().is_some();
+      return Some((*action, run));
+    }
+    // an upper case letter that isn't bound itself runs with its lower case movement key
+    let lower = name.to_lowercase();
+    match self.actions.get(&lower) {
+      Some(action) if lower != name && action.direction().is_some() => Some((*action, true)),
+      _ => None,
+    }
+  }
+
+  /// one line per action with the keys bound to it
+  pub fn help(&self) -> String {
+    let lines = self
+      .keys
+      .iter()
+      .map(|(action, keys)| format!("{:<12} {}", keys.join(" "), action.description()))
+      .collect::<Vec<_>>();
+    format!(
+      "Keys\n\n{}\n\nShift and a movement key runs.\nKeys can be changed in keymap.json.",
+      lines.join("\n")
+    )
+  }
+}
+
+fn is_key_name(name: &str) -> bool {
+  let name = name.trim_start_matches("Alt+");
+  name.chars().count() == 1 || NAMED_KEYS.iter().any(|(key_name, _)| *key_name == name)
+}
+
+/// the name a key press is bound by in the keymap
+fn key_name(key: Key) -> Option<String> {
+  let name = if key.code == KeyCode::Text {
+    key.text().to_string()
+  } else {
+    NAMED_KEYS.iter().find(|(_, code)| *code == key.code)?.0.to_string()
+  };
+  if key.alt {
+    Some(format!("Alt+{}", name))
+  } else {
+    Some(name)
+  }
+}
@@ -0,0 +1,195 @@
+use crate::damage::Resistances;
+use crate::equipment::Rarity;
+use crate::equipment::Slot;
+use crate::object::Object;
+use rand::{Rng, SeedableRng, StdRng};
+
+struct Base {
+  name: &'static str,
+  char: char,
+  slot: Slot,
+  power: i32,
+  defense: i32,
+  min_level: u32,
+}
+
+struct Affix {
+  name: &'static str,
+  min_level: u32,
+  power: i32,
+  defense: i32,
+  resistances: Resistances,
+}
+
+const NO_RESISTANCES: Resistances = Resistances { physical: 0, fire: 0, lightning: 0, cold: 0, poison: 0 };
+
+const BASES: &[Base] = &[
+  Base { name: "dagger", char: '/', slot: Slot::RightHand, power: 2, defense: 0, min_level: 1 },
+  Base { name: "sword", char: '/', slot: Slot::RightHand, power: 3, defense: 0, min_level: 1 },
+  Base { name: "axe", char: '/', slot: Slot::RightHand, power: 4, defense: 0, min_level: 3 },
+  Base { name: "shield", char: '[', slot: Slot::LeftHand, power: 0, defense: 1, min_level: 1 },
+  Base { name: "helmet", char: '[', slot: Slot::Head, power: 0, defense: 1, min_level: 2 },
+];
+
+const PREFIXES: &[Affix] = &[
+  Affix { name: "sharp", min_level: 1, power: 1, defense: 0, resistances: NO_RESISTANCES },
+  Affix { name: "sturdy", min_level: 1, power: 0, defense: 1, resistances: NO_RESISTANCES },
+  Affix {
+    name: "flaming",
+    min_level: 3,
+    power: 2,
+    defense: 0,
+    resistances: Resistances { fire: 20, ..NO_RESISTANCES },
+  },
+  Affix {
+    name: "icy",
+    min_level: 3,
+    power: 1,
+    defense: 0,
+    resistances: Resistances { cold: 25, ..NO_RESISTANCES },
+  },
+  Affix { name: "vicious", min_level: 5, power: 3, defense: 0, resistances: NO_RESISTANCES },
+];
+
+const SUFFIXES: &[Affix] = &[
+  Affix { name: "of accuracy", min_level: 1, power: 1, defense: 0, resistances: NO_RESISTANCES },
+  Affix { name: "of protection", min_level: 1, power: 0, defense: 1, resistances: NO_RESISTANCES },
+  Affix {
+    name: "of fire resistance",
+    min_level: 2,
+    power: 0,
+    defense: 0,
+    resistances: Resistances { fire: 30, ..NO_RESISTANCES },
+  },
+  Affix {
+    name: "of insulation",
+    min_level: 2,
+    power: 0,
+    defense: 0,
+    resistances: Resistances { lightning: 30, ..NO_RESISTANCES },
+  },
+  Affix {
+    name: "of warmth",
+    min_level: 2,
+    power: 0,
+    defense: 0,
+    resistances: Resistances { cold: 30, ..NO_RESISTANCES },
+  },
+  Affix {
+    name: "of the antidote",
+    min_level: 4,
+    power: 0,
+    defense: 0,
+    resistances: Resistances { poison: 30, ..NO_RESISTANCES },
+  },
+  Affix { name: "of the titan", min_level: 6, power: 2, defense: 1, resistances: NO_RESISTANCES },
+];
+
+/// Rolls equipment with random affixes. The same seed always yields the same loot.
+pub struct LootGenerator {
+  rng: StdRng,
+}
+
+impl LootGenerator {
+  pub fn new(seed: usize) -> Self {
+    LootGenerator {
+      rng: StdRng::from_seed(&[seed]),
+    }
+  }
+
+  pub fn generate(&mut self, x: i32, y: i32, level: u32) -> Object {
+    let bases = BASES.iter().filter(|base| base.min_level <= level).collect::<Vec<_>>();
+    let base = *self.rng.choose(&bases).unwrap();
+    let rarity = self.roll_rarity(level);
+
+    // magic items get one affix, better ones get both a prefix and a suffix
+    let (prefix, suffix) = match rarity {
+      Rarity::Common => (None, None),
+      Rarity::Magic => {
+        if self.rng.gen() {
+          (self.roll_affix(PREFIXES, level), None)
+        } else {
+          (None, self.roll_affix(SUFFIXES, level))
+        }
+      }
+      Rarity::Rare | Rarity::Legendary => (self.roll_affix(PREFIXES, level), self.roll_affix(SUFFIXES, level)),
+    };
+    // legendary affixes are twice as strong
+    let multiplier = if rarity == Rarity::Legendary { 2 } else { 1 };
+
+    let mut name = base.name.to_string();
+    let mut power = base.power;
+    let mut defense = base.defense;
+    let mut resistances = Resistances::default();
+    for affix in prefix.iter().chain(suffix.iter()) {
+      power += affix.power * multiplier;
+      defense += affix.defense * multiplier;
+      for _ in 0..multiplier {
+        resistances = resistances.add(&affix.resistances);
+      }
+    }
+    if let Some(prefix) = prefix {
+      name = format!("{} {}", prefix.name, name);
+    }
+    if let Some(suffix) = suffix {
+      name = format!("{} {}", name, suffix.name);
+    }
+
+    let mut object = Object::create_equipment(x, y, base.char, &name, base.slot, power, defense);
+    if let Some(ref mut equipment) = object.equipment {
+      equipment.resistances = resistances;
+      equipment.rarity = rarity;
+    }
+    if rarity != Rarity::Common {
+      object.color = rarity.color();
+    }
+    object
+  }
+
+  // deeper levels hold better loot
+  fn roll_rarity(&mut self, level: u32) -> Rarity {
+    let roll = self.rng.gen_range(0, 100);
+    let level = level as i32;
+    if roll < level {
+      Rarity::Legendary
+    } else if roll < level * 4 {
+      Rarity::Rare
+    } else if roll < 20 + level * 6 {
+      Rarity::Magic
+    } else {
+      Rarity::Common
+    }
+  }
+
+  fn roll_affix(&mut self, affixes: &'static [Affix], level: u32) -> Option<&'static Affix> {
+    let available = affixes.iter().filter(|affix| affix.min_level <= level).collect::<Vec<_>>();
+    self.rng.choose(&available).cloned()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // name, rarity and bonuses of an equipment roll
+  fn roll(loot: &mut LootGenerator, level: u32) -> (String, Rarity, i32, i32, Resistances) {
+    let object = loot.generate(0, 0, level);
+    let equipment = object.equipment.unwrap();
+    (object.name, equipment.rarity, equipment.power_bonus, equipment.defense_bonus, equipment.resistances)
+  }
+
+  fn rolls(seed: usize) -> Vec<(String, Rarity, i32, i32, Resistances)> {
+    let mut loot = LootGenerator::new(seed);
+    [1, 1, 3, 5, 8, 8, 10, 10].iter().map(|level| roll(&mut loot, *level)).collect()
+  }
+
+  #[test]
+  fn same_seed_same_loot() {
+    assert_eq!(rolls(42), rolls(42));
+  }
+
+  #[test]
+  fn different_seed_different_loot() {
+    assert_ne!(rolls(42), rolls(43));
+  }
+}
@@ -11,12 +11,30 @@ mod hud;
 mod input_output;
 mod transition;
 mod equipment;
+mod damage;
+mod projectile;
+mod identification;
+mod hunger;
+mod loot;
+mod shop;
+mod effect;
+mod examine;
+mod pickup;
+mod class;
+mod perk;
+mod spell;
+mod activity;
+mod keymap;
+mod targeting;
 
 use crate::game::next_level;
 use crate::game::initialise_fov;
 use crate::game::new_game;
+use crate::game::explore_fov;
 use crate::input_output::load_game;
 use crate::input_output::save_game;
+use crate::input_output::delete_save;
+use crate::input_output::load_keymap;
 use crate::hud::menu;
 
 use crate::hud::inventory_menu;
@@ -29,6 +47,14 @@ use crate::constants::*;
 
 
 use enemy::Enemy;
+use enemy::leave_corpses;
+use class::{Class, CLASSES};
+use perk::perk_info;
+use activity::{explore_step, first_step, hostile_in_view, keep_running, open_neighbours, Activity, Task};
+use keymap::{Action, Keymap};
+use targeting::{describe_tile, draw_cursor, look, visible_targets};
+use examine::describe;
+use pickup::{auto_pickup, auto_pickup_options, pick_up};
 use player::Player;
 use object::Object;
 use game::Game;
@@ -47,6 +73,9 @@ pub struct Tcod {
   fov: FovMap,
   key: Key,
   mouse: Mouse,
+  keymap: Keymap,
+  // set when the map changed around the player, so the FOV is computed again
+  fov_recompute: bool,
 }
 
 
@@ -54,48 +83,213 @@ pub struct Tcod {
 fn play_game(mut tcod: &mut Tcod, mut game: &mut Game, mut player: &mut Player, mut enemies: &mut Vec<Enemy>, mut collectibles: &mut Vec<Object>) {
   // force FOV "recompute" first time through the game loop
   let mut previous_player_position = (-1, -1);
+  // energy the player spent that monsters haven't used yet
+  let mut monster_energy = 0;
 
   while !tcod.root.window_closed() {
+    // clicks and key presses only count on the frame they happen
+    tcod.key = Default::default();
+    tcod.mouse.lbutton_pressed = false;
+    tcod.mouse.rbutton_pressed = false;
     match input::check_for_event(input::MOUSE | input::KEY_PRESS) {
       Some((_, Event::Mouse(m))) => tcod.mouse = m,
       Some((_, Event::Key(k))) => tcod.key = k,
-      _ => tcod.key = Default::default(),
+      _ => {}
     }
 
     tcod.con.clear();
     tcod.panel.clear();
     
 
-    let fov_recompute = previous_player_position != (player.pos());
+    let fov_recompute = previous_player_position != (player.pos()) || tcod.fov_recompute;
+    tcod.fov_recompute = false;
     render_game(&mut tcod, &game, &player, &enemies, &collectibles, fov_recompute);
+    if fov_recompute {
+      explore_fov(&tcod, &mut game);
+    }
     render_gui(&mut tcod, &game, &player, &enemies);
     tcod.root.flush();
 
     previous_player_position = player.pos();
-    let player_action = handle_keys(&mut tcod, &mut game, &mut player, &mut enemies, &mut collectibles);
+    let player_action = if game.activity.is_some() {
+      continue_activity(&mut tcod, &mut game, &mut player, &mut enemies, &collectibles)
+    } else {
+      handle_keys(&mut tcod, &mut game, &mut player, &mut enemies, &mut collectibles)
+    };
+    leave_corpses(&mut enemies, &mut collectibles, game.dungeon_level);
+    if player.pos() != previous_player_position {
+      auto_pickup(&mut game, &mut player, &mut collectibles);
+    }
     if player_action == PlayerAction::Exit {
       save_game(game, player, enemies, collectibles).unwrap();
       break;
     }
 
-    // let monsters take their turn
+    // let monsters take their turn, as many times as the player's action allows
     if player.is_alive() && player_action != PlayerAction::DidntTakeTurn {
-      for id in 0..enemies.len() {
-        if enemies[id].get_ai().is_some() {
-            let mut enemies_without_enemy = enemies.clone();
-            enemies_without_enemy.clone_from_slice(&enemies);
-            let mut enemy = enemies_without_enemy.remove(id);
-            enemy.ai_take_turn(&tcod, &mut game, &enemies_without_enemy, &mut player);
-            
-            // update the original other Enemy array
-            enemies[id] = enemy;
+      monster_energy += player.action_cost(game);
+      while monster_energy >= ACTION_COST {
+        monster_energy -= ACTION_COST;
+        for id in 0..enemies.len() {
+          // once the player is dead the remaining monsters have nothing left to do
+          if enemies[id].get_ai().is_some() && player.is_alive() {
+              let mut enemies_without_enemy = enemies.clone();
+              enemies_without_enemy.clone_from_slice(&enemies);
+              let mut enemy = enemies_without_enemy.remove(id);
+              enemy.ai_take_turn(&tcod, &mut game, &enemies_without_enemy, &mut player);
+              
+              // update the original other Enemy array
+              enemies[id] = enemy;
+          }
         }
       }
-      player.level_up(tcod, game);
+      if !player.is_alive() {
+        game_over(tcod, game, player, enemies, collectibles);
+        break;
+      }
+      player.pass_turn(game);
+      game.turns += 1;
+      if player.is_alive() {
+        player.level_up(tcod, game);
+      }
+    }
+
+    if !player.is_alive() {
+      game_over(tcod, game, player, enemies, collectibles);
+      break;
     }
   }
 }
 
+/// show the dead character one last time with a summary of the run, then delete the save
+fn game_over(tcod: &mut Tcod, game: &mut Game, player: &Player, enemies: &[Enemy], collectibles: &[Object]) {
+  game.activity = None;
+  tcod.con.clear();
+  render_game(tcod, game, player, enemies, collectibles, false);
+  render_gui(tcod, game, player, enemies);
+  tcod.root.flush();
+
+  let last_messages = game
+    .messages
+    .iter()
+    .rev()
+    .take(GAME_OVER_MESSAGES)
+    .map(|(message, _)| message.as_str())
+    .collect::<Vec<_>>();
+  let last_messages = last_messages.into_iter().rev().collect::<Vec<_>>().join("\n");
+  let msg = format!(
+    "You died on dungeon level {} after {} turns.
+
+    Class: {}
+    Level: {}
+    Experience: {}
+    Gold: {}
+
+    Last messages:
+{}",
+    game.dungeon_level,
+    game.turns,
+    player.get_class(),
+    player.get_level(),
+    player.get_fighter().map_or(0, |f| f.xp),
+    player.get_gold(),
+    last_messages
+  );
+  msgbox(&msg, GAME_OVER_SCREEN_WIDTH, &mut tcod.root);
+
+  if delete_save().is_err() {
+    msgbox("\nThe saved game could not be deleted.\n", GAME_OVER_SCREEN_WIDTH, &mut tcod.root);
+  }
+}
+
+/// take the next turn of the current activity, or stop it if the player pressed a key or is in danger
+fn continue_activity(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies: &mut [Enemy], collectibles: &[Object]) -> PlayerAction {
+  let mut activity = match game.activity.take() {
+    Some(activity) => activity,
+    None => return PlayerAction::DidntTakeTurn,
+  };
+  if tcod.key.code != tcod::input::KeyCode::NoKey || tcod.mouse.lbutton_pressed {
+    game.messages.add(format!("You stop {}.", activity.task), tcod::colors::WHITE);
+    return PlayerAction::DidntTakeTurn;
+  }
+  if let Some(reason) = activity.danger(tcod, game, player, enemies) {
+    game.messages.add(format!("{} You stop {}.", reason, activity.task), tcod::colors::ORANGE);
+    return PlayerAction::DidntTakeTurn;
+  }
+  activity.watch(game, player);
+  match activity.task {
+    Task::Rest => {
+      if player.is_healed() {
+        game.messages.add("You feel rested.", tcod::colors::LIGHT_GREEN);
+        return PlayerAction::DidntTakeTurn;
+      }
+    }
+    Task::Explore => match explore_step(game, player, enemies, collectibles) {
+      Some((dx, dy)) => player.move_or_attack(dx, dy, game, tcod, enemies),
+      None => {
+        game.messages.add("There is nothing left to explore here.", tcod::colors::WHITE);
+        return PlayerAction::DidntTakeTurn;
+      }
+    },
+    Task::Run { dx, dy, open } => {
+      if !keep_running(game, player, enemies, collectibles, dx, dy, open) {
+        return PlayerAction::DidntTakeTurn;
+      }
+      let open = open_neighbours(game, player.get_x(), player.get_y());
+      player.move_or_attack(dx, dy, game, tcod, enemies);
+      activity.task = Task::Run { dx, dy, open };
+    }
+    Task::Travel { x, y } => {
+      if player.pos() == (x, y) {
+        return PlayerAction::DidntTakeTurn;
+      }
+      match first_step(game, enemies, player.pos(), |goal_x, goal_y| (goal_x, goal_y) == (x, y)) {
+        Some((dx, dy)) => player.move_or_attack(dx, dy, game, tcod, enemies),
+        None => {
+          game.messages.add("You don't know a way there.", tcod::colors::WHITE);
+          return PlayerAction::DidntTakeTurn;
+        }
+      }
+    }
+  }
+  game.activity = Some(activity);
+  PlayerAction::TookTurn
+}
+
+/// attack an adjacent monster that was clicked, or travel to a known floor tile
+fn click(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies: &mut [Enemy]) -> PlayerAction {
+  let (x, y) = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
+  if x >= MAP_WIDTH || y >= MAP_HEIGHT || (x, y) == player.pos() {
+    return PlayerAction::DidntTakeTurn;
+  }
+  let (dx, dy) = (x - player.get_x(), y - player.get_y());
+  let monster_there = enemies
+    .iter()
+    .any(|enemy| enemy.get_fighter().is_some() && enemy.pos() == (x, y) && tcod.fov.is_in_fov(x, y));
+  if monster_there && dx.abs().max(dy.abs()) == 1 {
+    player.move_or_attack(dx, dy, game, tcod, enemies);
+    return PlayerAction::TookTurn;
+  }
+  let tile = &game.map[x as usize][y as usize];
+  if !tile.is_explored() || tile.is_blocked() || monster_there {
+    return PlayerAction::DidntTakeTurn;
+  }
+  if hostile_in_view(tcod, enemies) {
+    game.messages.add("You can't travel with enemies nearby.", tcod::colors::WHITE);
+  } else {
+    game.activity = Some(Activity::new(Task::Travel { x, y }, game, player));
+  }
+  PlayerAction::DidntTakeTurn
+}
+
+fn choose_class(tcod: &mut Tcod) -> Option<Class> {
+  let options = CLASSES
+    .iter()
+    .map(|class| format!("{}: {}", class, class.description()))
+    .collect::<Vec<_>>();
+  menu("Choose your class:\n", &options, CLASS_SCREEN_WIDTH, &mut tcod.root).map(|choice| CLASSES[choice])
+}
+
 fn main_menu(mut tcod: &mut Tcod) {
   let img = tcod::image::Image::from_file("menu_background.png") 
       .ok()
@@ -130,9 +324,13 @@ fn main_menu(mut tcod: &mut Tcod) {
 
     match choice {
       Some(0) => {
-          // new game
-          let (mut game, mut player, mut enemies, mut collectibles) = new_game(&mut tcod);
-          play_game(&mut tcod, &mut game, &mut player, &mut enemies, &mut collectibles);
+          // new game, once a class is chosen
+          if let Some(class) = choose_class(tcod) {
+            let (mut game, mut player, mut enemies, mut collectibles) = new_game(&mut tcod, class);
+            // the new character takes the place of the saved one, so its death only deletes its own save
+            save_game(&game, &player, &enemies, &collectibles).unwrap();
+            play_game(&mut tcod, &mut game, &mut player, &mut enemies, &mut collectibles);
+          }
       }
       Some(1) => {
         // load game
@@ -159,94 +357,218 @@ fn main_menu(mut tcod: &mut Tcod) {
 
 
 
+/// inventory menu header, with the weight carried
+fn inventory_header(text: &str, game: &Game, player: &Player) -> String {
+  let burdened = if player.is_burdened(game) { " (burdened)" } else { "" };
+  format!("Load: {}/{}{}\n{}\n", player.load(game), player.capacity(), burdened, text)
+}
+
 fn msgbox(text: &str, width: i32, root: &mut Root) {
     let options: &[&str] = &[];
     menu(text, options, width, root);
 }
 
-pub fn target_tile(tcod: &mut Tcod, game: &mut Game, player: &Player, enemies: &[Enemy], collectibles: &[Object], max_range: Option<f32>) -> Option<(i32, i32)> {
-    use tcod::input::KeyCode::Escape;
-    loop {
-        // render the screen. this erases the inventory and shows the names of
-        // objects under the mouse.
-        tcod.root.flush();
-        let event = input::check_for_event(input::KEY_PRESS | input::MOUSE).map(|e| e.1);
-        match event {
-            Some(Event::Mouse(m)) => tcod.mouse = m,
-            Some(Event::Key(k)) => tcod.key = k,
-            None => tcod.key = Default::default(),
-        }
-        render_game(tcod, &game, &player, &enemies, &collectibles, false);
-
-        let (x, y) = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
+/// Choose a tile with the mouse, or with a cursor moved by the movement keys. Tab jumps between
+/// visible enemies. Tiles in range are tinted, as are the tiles an area effect of `radius` would hit.
+/// Returns None if the player cancels with Escape or a right-click.
+pub fn target_tile(
+  tcod: &mut Tcod,
+  game: &mut Game,
+  player: &Player,
+  enemies: &[Enemy],
+  collectibles: &[Object],
+  max_range: Option<f32>,
+  radius: Option<i32>,
+) -> Option<(i32, i32)> {
+  use tcod::input::KeyCode;
+
+  let targets = visible_targets(tcod, player, enemies, max_range);
+  let mut cursor = targets.first().cloned().unwrap_or_else(|| player.pos());
+  loop {
+    tcod.con.clear();
+    render_game(tcod, game, player, enemies, collectibles, false);
+    render_gui(tcod, game, player, enemies);
+    draw_cursor(tcod, player, cursor, max_range, radius);
+    tcod.root.set_default_foreground(tcod::colors::LIGHT_GREY);
+    tcod.root.print_ex(
+      1,
+      0,
+      BackgroundFlag::Set,
+      TextAlignment::Left,
+      describe_tile(cursor.0, cursor.1, tcod, game, player, enemies, collectibles),
+    );
+    tcod.root.flush();
 
-        // accept the target if the player clicked in FOV, and in case a range
-        // is specified, if it's in that range
-        let in_fov = (x < MAP_WIDTH) && (y < MAP_HEIGHT) && tcod.fov.is_in_fov(x, y);
-        let in_range = max_range.map_or(true, |range| player.distance(x, y) <= range);
-        if tcod.mouse.lbutton_pressed && in_fov && in_range {
-            return Some((x, y));
+    tcod.key = Default::default();
+    tcod.mouse.lbutton_pressed = false;
+    tcod.mouse.rbutton_pressed = false;
+    match input::check_for_event(input::KEY_PRESS | input::MOUSE).map(|e| e.1) {
+      Some(Event::Mouse(m)) => {
+        let (x, y) = (m.cx as i32, m.cy as i32);
+        if (x, y) != (tcod.mouse.cx as i32, tcod.mouse.cy as i32) && x < MAP_WIDTH && y < MAP_HEIGHT {
+          cursor = (x, y);
         }
+        tcod.mouse = m;
+      }
+      Some(Event::Key(k)) => tcod.key = k,
+      None => {}
+    }
 
-        if tcod.mouse.rbutton_pressed || tcod.key.code == Escape {
-            return None; // cancel if the player right-clicked or pressed Escape
+    let valid = |(x, y): (i32, i32)| {
+      // a target must be in FOV, and in range if a range is specified
+      x < MAP_WIDTH
+        && y < MAP_HEIGHT
+        && tcod.fov.is_in_fov(x, y)
+        && max_range.map_or(true, |range| player.distance(x, y) <= range)
+    };
+    if tcod.mouse.lbutton_pressed {
+      let clicked = (tcod.mouse.cx as i32, tcod.mouse.cy as i32);
+      if valid(clicked) {
+        return Some(clicked);
+      }
+    }
+    if tcod.mouse.rbutton_pressed || tcod.key.code == KeyCode::Escape {
+      return None; // cancel if the player right-clicked or pressed Escape
+    }
+    match tcod.key.code {
+      KeyCode::Enter | KeyCode::NumPadEnter if valid(cursor) => return Some(cursor),
+      KeyCode::Tab if !targets.is_empty() => {
+        // cycle through the visible enemies, from the closest one
+        let current = targets.iter().position(|target| *target == cursor);
+        let next = match (current, tcod.key.shift) {
+          (Some(id), false) => (id + 1) % targets.len(),
+          (Some(id), true) => (id + targets.len() - 1) % targets.len(),
+          (None, _) => 0,
+        };
+        cursor = targets[next];
+      }
+      _ => {
+        if let Some((action, jump)) = tcod.keymap.action(tcod.key) {
+          if let Some((dx, dy)) = action.direction() {
+            let distance = if jump { CURSOR_JUMP } else { 1 };
+            cursor = (
+              (cursor.0 + dx * distance).clamp(0, MAP_WIDTH - 1),
+              (cursor.1 + dy * distance).clamp(0, MAP_HEIGHT - 1),
+            );
+          }
         }
+      }
     }
+  }
 }
 
 fn handle_keys(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies: &mut Vec<Enemy>, collectibles: &mut Vec<Object>) -> PlayerAction {
-  use tcod::input::KeyCode::*;
   use PlayerAction::*;
 
-  match (tcod.key, tcod.key.text(), player.is_alive()) {
-    (
-      Key {
-          code: Enter,
-          alt: true,
-          ..
-      },
-      _,
-      _,
-    ) => {
-      // Alt+Enter: toggle fullscreen
+  if tcod.mouse.lbutton_pressed && player.is_alive() {
+    return click(tcod, game, player, enemies);
+  }
+
+  let (action, run) = match tcod.keymap.action(tcod.key) {
+    Some(bound) => bound,
+    None => return DidntTakeTurn,
+  };
+
+  // movement keys, holding shift runs until something interesting happens
+  if let (Some((dx, dy)), true) = (action.direction(), player.is_alive()) {
+    let start = player.pos();
+    let open = open_neighbours(game, start.0, start.1);
+    player.move_or_attack(dx, dy, game, tcod, enemies);
+    if run && player.pos() != start && !hostile_in_view(tcod, enemies) {
+      game.activity = Some(Activity::new(Task::Run { dx, dy, open }, game, player));
+    }
+    return TookTurn;
+  }
+
+  match (action, player.is_alive()) {
+    (Action::Fullscreen, _) => {
+      // toggle fullscreen
       let fullscreen = tcod.root.is_fullscreen();
       tcod.root.set_fullscreen(!fullscreen);
       DidntTakeTurn
     }
-    (Key { code: Escape, .. }, _, _) => Exit, // exit game
+    (Action::Exit, _) => Exit, // exit game
+    (Action::Help, _) => {
+      // list the keys of the active keymap
+      msgbox(&tcod.keymap.help(), HELP_SCREEN_WIDTH, &mut tcod.root);
+      DidntTakeTurn
+    }
 
-    // movement keys
-    (Key { code: Up, .. }, _, true) => {
-      player.move_or_attack(0, -1, game, enemies);
+    (Action::Wait, true) => {
+      // wait a turn
       TookTurn
     }
-    (Key { code: Down, .. }, _, true) => {
-      player.move_or_attack(0, 1, game, enemies);
-      TookTurn
+    (Action::Rest, true) => {
+      // rest until healed, or until something happens
+      if player.is_healed() {
+        game.messages.add("You are already at full health.", tcod::colors::WHITE);
+      } else if hostile_in_view(tcod, enemies) {
+        game.messages.add("You can't rest with enemies nearby.", tcod::colors::WHITE);
+      } else {
+        game.messages.add("You start resting.", tcod::colors::WHITE);
+        game.activity = Some(Activity::new(Task::Rest, game, player));
+      }
+      DidntTakeTurn
     }
-    (Key { code: Left, .. }, _, true) => {
-      player.move_or_attack(-1, 0, game, enemies);
-      TookTurn
+    (Action::Explore, true) => {
+      // explore the level until something interesting happens
+      if hostile_in_view(tcod, enemies) {
+        game.messages.add("You can't explore with enemies nearby.", tcod::colors::WHITE);
+      } else {
+        game.activity = Some(Activity::new(Task::Explore, game, player));
+      }
+      DidntTakeTurn
     }
-    (Key { code: Right, .. }, _, true) => {
-      player.move_or_attack(1, 0, game, enemies);
-      TookTurn
+    (Action::TravelToStairs, true) => {
+      // travel to the stairs, once they have been seen
+      let stairs = collectibles
+        .iter()
+        .find(|object| object.get_name() == "stairs" && game.map[object.x as usize][object.y as usize].is_explored())
+        .map(|stairs| stairs.pos());
+      match stairs {
+        Some((x, y)) if hostile_in_view(tcod, enemies) && player.pos() != (x, y) => {
+          game.messages.add("You can't travel with enemies nearby.", tcod::colors::WHITE);
+        }
+        Some((x, y)) => game.activity = Some(Activity::new(Task::Travel { x, y }, game, player)),
+        None => game.messages.add("You haven't found the stairs yet.", tcod::colors::WHITE),
+      }
+      DidntTakeTurn
     }
-    (Key { code: Text, .. }, "g", true) => {
-      // pick up an item
-      let item_id = collectibles
-          .iter()
-          .position(|object| object.pos() == player.pos() && object.item.is_some());
-      if let Some(item_id) = item_id {
-          player.pick_item_up(item_id, game, collectibles);
+    (Action::PickUp, true) => {
+      // pick up an item, or choose among the ones lying here
+      pick_up(tcod, game, player, collectibles);
+      DidntTakeTurn
+    }
+    (Action::CastSpell, true) => {
+      // cast a spell from the spellbook
+      if player.cast_spell(tcod, game, collectibles, enemies) {
+        TookTurn
+      } else {
+        DidntTakeTurn
       }
+    }
+    (Action::ChoosePerk, true) => {
+      player.choose_perk(tcod, game);
       DidntTakeTurn
     }
-    (Key { code: Text, .. }, "i", true) => {
+    (Action::AutoPickupOptions, true) => {
+      auto_pickup_options(tcod, game);
+      DidntTakeTurn
+    }
+    (Action::Fire, true) => {
+      // shoot with the equipped ranged weapon
+      if player.fire(None, game, tcod, collectibles, enemies) {
+        TookTurn
+      } else {
+        DidntTakeTurn
+      }
+    }
+    (Action::Inventory, true) => {
       // show the inventory: if an item is selected, use it
       let inventory_index = inventory_menu(
         &game.inventory,
-        "Press the key next to an item to use it, or any other to cancel.\n",
+        &game.identification,
+        &inventory_header("Press the key next to an item to use it, or any other to cancel.", game, player),
         &mut tcod.root,
       );
       if let Some(inventory_index) = inventory_index {
@@ -254,18 +576,51 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies: &
       }
       DidntTakeTurn
     }
-    (Key { code: Text, .. }, "d", true) => {
+    (Action::Look, true) => {
+      look(tcod, game, player, enemies, collectibles);
+      DidntTakeTurn
+    }
+    (Action::Examine, true) => {
+      // show the inventory; if an item is selected, describe it
+      let inventory_index = inventory_menu(
+        &game.inventory,
+        &game.identification,
+        &inventory_header("Press the key next to an item to examine it, or any other to cancel.", game, player),
+        &mut tcod.root,
+      );
+      if let Some(inventory_index) = inventory_index {
+        let description = describe(&game.inventory[inventory_index], &game.inventory, &game.identification);
+        msgbox(&description, INVENTORY_WIDTH, &mut tcod.root);
+      }
+      DidntTakeTurn
+    }
+    (Action::Throw, true) => {
+      // show the inventory; if an item is selected, throw it
+      let inventory_index = inventory_menu(
+        &game.inventory,
+        &game.identification,
+        &inventory_header("Press the key next to an item to throw it, or any other to cancel.", game, player),
+        &mut tcod.root,
+      );
+      match inventory_index {
+        Some(inventory_index) if player.throw_item(inventory_index, game, tcod, collectibles, enemies) => TookTurn,
+        _ => DidntTakeTurn,
+      }
+    }
+    (Action::Drop, true) => {
       // show the inventory; if an item is selected, drop it
       let inventory_index = inventory_menu(
         &game.inventory,
-        "Press the key next to an item to drop it, or any other to cancel.\n'",
+        &game.identification,
+        &inventory_header("Press the key next to an item to drop it, or any other to cancel.", game, player),
         &mut tcod.root,
       );
       if let Some(inventory_index) = inventory_index {
-        player.drop_item(inventory_index, game, collectibles);
+        player.drop_item(inventory_index, game, tcod, collectibles);
       }
       DidntTakeTurn
-    }(Key { code: Text, .. }, "<", true) => {
+    }
+    (Action::Descend, true) => {
       // go down stairs, if the player is on them
       let player_on_stairs = collectibles
         .iter()
@@ -275,21 +630,44 @@ fn handle_keys(tcod: &mut Tcod, game: &mut Game, player: &mut Player, enemies: &
       }
       DidntTakeTurn
     }
-    (Key { code: Text, .. }, "c", true) => {
+    (Action::Discoveries, true) => {
+      // list the potions and scrolls identified so far
+      let discoveries = game.identification.discoveries();
+      let msg = if discoveries.is_empty() {
+        "You haven't identified anything yet.".to_string()
+      } else {
+        format!("Discoveries\n\n{}", discoveries.join("\n"))
+      };
+      msgbox(&msg, INVENTORY_WIDTH, &mut tcod.root);
+      DidntTakeTurn
+    }
+    (Action::Character, true) => {
       // show character information
       let level = player.get_level();
       let level_up_xp = player.level_up_xp();
+      let power = player.power(game);
+      let defense = player.defense(game);
+      let perks = player
+        .get_perks()
+        .iter()
+        .map(|perk| perk_info(*perk).name)
+        .collect::<Vec<_>>();
+      let perks = if perks.is_empty() { "none".to_string() } else { perks.join(", ") };
       if let Some(fighter) = player.get_fighter() {
         let msg = format!(
           "Character information
+          Class: {}
           Level: {}
           Experience: {}
           Experience to level up: {}
 
           Maximum HP: {}
+          Maximum mana: {}
           Attack: {}
-          Defense: {}",
-          level, fighter.xp, level_up_xp, fighter.max_hp, fighter.power, fighter.defense
+          Defense: {}
+
+          Perks: {}",
+          player.get_class(), level, fighter.xp, level_up_xp, fighter.max_hp, player.get_max_mana(), power, defense, perks
         );
         msgbox(&msg, CHARACTER_SCREEN_WIDTH, &mut tcod.root);
       }
@@ -310,7 +688,8 @@ fn render_game(tcod: &mut Tcod, game: &Game, player: &Player, enemies: &[Enemy],
   // draw all collectibles
   for collectible in collectibles {
     let (x, y) = collectible.pos();
-    if tcod.fov.is_in_fov(x, y) || collectible.always_visible() {
+    let explored = game.map[x as usize][y as usize].is_explored();
+    if tcod.fov.is_in_fov(x, y) || (collectible.always_visible() && explored) {
       collectible.draw(&mut tcod.con);
     }
   }
@@ -327,6 +706,10 @@ fn render_game(tcod: &mut Tcod, game: &Game, player: &Player, enemies: &[Enemy],
   for y in 0..MAP_HEIGHT {
     for x in 0..MAP_WIDTH {
       let visible = tcod.fov.is_in_fov(x, y);
+      if !visible && !game.map[x as usize][y as usize].is_explored() {
+        // unexplored tiles stay black
+        continue;
+      }
       let wall = game.map[x as usize][y as usize].is_block_sight();
       let color = match (visible, wall) {
           // outside of field of view:
@@ -337,6 +720,10 @@ fn render_game(tcod: &mut Tcod, game: &Game, player: &Player, enemies: &[Enemy],
           (true, false) => COLOR_LIGHT_GROUND,
       };
       tcod.con.set_char_background(x, y, color, BackgroundFlag::Set);
+      if game.map[x as usize][y as usize].is_locked() {
+        tcod.con.set_default_foreground(COLOR_DOOR);
+        tcod.con.put_char(x, y, '+', BackgroundFlag::None);
+      }
     }
   }
   blit(
@@ -368,7 +755,13 @@ fn main() {
     fov: FovMap::new(MAP_WIDTH, MAP_HEIGHT),
     key: Default::default(),
     mouse: Default::default(),
+    keymap: Keymap::default(),
+    fov_recompute: false,
   };
 
+  match load_keymap() {
+    Ok(keymap) => tcod.keymap = keymap,
+    Err(e) => msgbox(&format!("\nError in keymap.json, using the default keys:\n{}\n", e), HELP_SCREEN_WIDTH, &mut tcod.root),
+  }
   main_menu(&mut tcod);
 }
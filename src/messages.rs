@@ -15,6 +15,10 @@ impl Messages {
     self.messages.push((message.into(), color));
   }
 
+  pub fn count(&self) -> usize {
+    self.messages.len()
+  }
+
   pub fn iter(&self) -> impl DoubleEndedIterator<Item = &(String, Color)> {
     self.messages.iter()
   }
@@ -1,13 +1,25 @@
+use crate::constants::THROWN_ITEM_DAMAGE;
+use crate::constants::THROWN_WEAPON_DAMAGE;
+use crate::constants::WAND_MIN_CHARGES;
+use crate::constants::WAND_MAX_CHARGES;
 use crate::messages::Messages;
+use crate::damage::Resistances;
+use crate::equipment::Ammo;
+use crate::equipment::Blessing;
+use crate::equipment::Rarity;
 use crate::equipment::Equipment;
+use crate::equipment::RangedWeapon;
 use crate::equipment::Slot;
 use crate::fighter::Fighter;
+use crate::identification::Identification;
 use crate::game::Game;
 use crate::game::Map;
 use tcod::colors::Color;
 use tcod::console::Console;
 use tcod::console::BackgroundFlag;
 use serde::{Deserialize, Serialize};
+use rand::Rng;
+use std::cmp;
 
 #[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Item {
@@ -16,6 +28,109 @@ pub enum Item {
     Confuse,
     Fireball,
     Equipment,
+    Ammo(Ammo),
+    ConfusionPotion,
+    Identify,
+    LightningWand,
+    ConfusionWand,
+    FireballWand,
+    DiggingWand,
+    Recharge,
+    RemoveCurse,
+    Ration,
+    Corpse,
+    Gold,
+    Key,
+    PoisonPotion,
+    MassConfusion,
+    Teleport,
+}
+
+impl Item {
+  /// potions shatter when thrown
+  pub fn is_potion(&self) -> bool {
+    matches!(*self, Item::Heal | Item::ConfusionPotion | Item::PoisonPotion)
+  }
+
+  /// wands keep their charges between uses
+  pub fn is_wand(&self) -> bool {
+    matches!(*self, Item::LightningWand | Item::ConfusionWand | Item::FireballWand | Item::DiggingWand)
+  }
+
+  /// consumables pile up in a single inventory entry
+  pub fn is_stackable(&self) -> bool {
+    !matches!(*self, Item::Equipment) && !self.is_wand()
+  }
+
+  /// what the examine screen says about an identified item
+  pub fn description(&self) -> &'static str {
+    match *self {
+      Item::Heal => "A potion that closes wounds.",
+      Item::Lightning => "A scroll that calls down a lightning bolt.",
+      Item::Confuse => "A scroll that clouds the mind of an enemy.",
+      Item::Fireball => "A scroll that conjures an exploding ball of fire.",
+      Item::Equipment => "Something to wield or wear.",
+      Item::Ammo(_) => "Ammunition, shot with the matching ranged weapon.",
+      Item::ConfusionPotion => "A potion that makes the drinker dizzy.",
+      Item::Identify => "A scroll that reveals the nature of another item.",
+      Item::LightningWand => "A wand crackling with electricity.",
+      Item::ConfusionWand => "A wand that clouds the mind of an enemy.",
+      Item::FireballWand => "A wand that shoots exploding balls of fire.",
+      Item::DiggingWand => "A wand that turns rock into dust along a line.",
+      Item::Recharge => "A scroll that restores charges to a wand.",
+      Item::RemoveCurse => "A scroll that lifts the curse from everything you wear.",
+      Item::Ration => "A nourishing meal, good for a long time.",
+      Item::Corpse => "The remains of a monster. Edible, if you are not picky.",
+      Item::Gold => "Shiny coins, welcome in every shop.",
+      Item::Key => "A heavy key that opens a vault door.",
+      Item::PoisonPotion => "A potion that makes the drinker very sick.",
+      Item::MassConfusion => "A scroll that clouds the mind of every enemy in sight.",
+      Item::Teleport => "A scroll that sends the reader somewhere else on the level.",
+    }
+  }
+
+  /// weight of a single item, equipment weighs according to its slot instead
+  pub fn weight(&self) -> i32 {
+    match *self {
+      Item::Heal | Item::ConfusionPotion | Item::PoisonPotion => 4,
+      Item::Lightning | Item::Confuse | Item::Fireball | Item::Identify | Item::Recharge
+        | Item::RemoveCurse | Item::MassConfusion | Item::Teleport => 1,
+      Item::LightningWand | Item::ConfusionWand | Item::FireballWand | Item::DiggingWand => 5,
+      Item::Equipment => 0,
+      Item::Ammo(_) => 1,
+      Item::Ration => 10,
+      Item::Corpse => 80,
+      Item::Gold => 0,
+      Item::Key => 2,
+    }
+  }
+
+  /// price in gold before charges and equipment bonuses are taken into account
+  pub fn base_price(&self) -> i32 {
+    match *self {
+      Item::Heal => 50,
+      Item::Lightning => 100,
+      Item::Confuse => 80,
+      Item::Fireball => 150,
+      Item::Equipment => 20,
+      Item::Ammo(_) => 2,
+      Item::ConfusionPotion => 40,
+      Item::Identify => 60,
+      Item::LightningWand => 200,
+      Item::ConfusionWand => 150,
+      Item::FireballWand => 300,
+      Item::DiggingWand => 150,
+      Item::Recharge => 200,
+      Item::RemoveCurse => 120,
+      Item::Ration => 20,
+      Item::Corpse => 1,
+      Item::Gold => 1,
+      Item::Key => 50,
+      Item::PoisonPotion => 30,
+      Item::MassConfusion => 150,
+      Item::Teleport => 100,
+    }
+  }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -30,7 +145,9 @@ pub struct Object {
   pub fighter: Option<Fighter>,
   pub item: Option<Item>,
   pub always_visible: bool,
-  pub equipment: Option<Equipment>
+  pub equipment: Option<Equipment>,
+  pub quantity: i32,
+  pub charges: Option<i32>,
 }
 
 impl Object {
@@ -46,7 +163,9 @@ impl Object {
       fighter: None,
       item: None,
       always_visible: false,
-      equipment: None
+      equipment: None,
+      quantity: 1,
+      charges: None,
     }
   }
 
@@ -62,7 +181,27 @@ impl Object {
       fighter: None,
       item: Some(Item::Heal),
       always_visible: true,
-      equipment: None
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_confusion_potion(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '!',
+      name: "potion of confusion".to_string(),
+      color: tcod::colors::VIOLET,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::ConfusionPotion),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
     }
   }
 
@@ -79,6 +218,8 @@ impl Object {
       item: Some(Item::Lightning),
       always_visible: true,
       equipment: None,
+      quantity: 1,
+      charges: None,
     }
   }
 
@@ -95,6 +236,8 @@ impl Object {
       item: Some(Item::Confuse),
       always_visible: true,
       equipment: None,
+      quantity: 1,
+      charges: None,
     }
   }
 
@@ -110,7 +253,191 @@ impl Object {
       fighter: None,
       item: Some(Item::Fireball),
       always_visible: true,
-      equipment: None
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_identify_scroll(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '#',
+      name: "scroll of identify".to_string(),
+      color: tcod::colors::LIGHT_YELLOW,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Identify),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_recharge_scroll(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '#',
+      name: "scroll of recharging".to_string(),
+      color: tcod::colors::LIGHT_YELLOW,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Recharge),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_remove_curse_scroll(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '#',
+      name: "scroll of remove curse".to_string(),
+      color: tcod::colors::LIGHT_YELLOW,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::RemoveCurse),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_ration(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '&',
+      name: "food ration".to_string(),
+      color: tcod::colors::LIGHT_SEPIA,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Ration),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_corpse(x: i32, y: i32, monster_name: &str) -> Self {
+    Object {
+      x,
+      y,
+      char: '%',
+      name: format!("{} corpse", monster_name),
+      color: tcod::colors::DARK_RED,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Corpse),
+      always_visible: false,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_gold(x: i32, y: i32, amount: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '$',
+      name: "gold piece".to_string(),
+      color: tcod::colors::GOLD,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Gold),
+      always_visible: true,
+      equipment: None,
+      quantity: amount,
+      charges: None,
+    }
+  }
+
+  pub fn create_key(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '(',
+      name: "vault key".to_string(),
+      color: tcod::colors::YELLOW,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Key),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  /// a potion that does nothing but apply its effects
+  pub fn create_potion_of(x: i32, y: i32, item: Item, name: &str, color: Color) -> Self {
+    Object {
+      x,
+      y,
+      char: '!',
+      name: name.to_string(),
+      color,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(item),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  /// a scroll that does nothing but apply its effects
+  pub fn create_scroll(x: i32, y: i32, item: Item, name: &str) -> Self {
+    Object {
+      x,
+      y,
+      char: '#',
+      name: name.to_string(),
+      color: tcod::colors::LIGHT_YELLOW,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(item),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_wand(x: i32, y: i32, item: Item, name: &str) -> Self {
+    Object {
+      x,
+      y,
+      char: '-',
+      name: name.to_string(),
+      color: tcod::colors::LIGHT_AZURE,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(item),
+      always_visible: true,
+      equipment: None,
+      quantity: 1,
+      charges: Some(rand::thread_rng().gen_range(WAND_MIN_CHARGES, WAND_MAX_CHARGES + 1)),
     }
   }
 
@@ -126,7 +453,133 @@ impl Object {
       fighter: None,
       item: Some(Item::Equipment),
       always_visible: true,
-      equipment: Some(Equipment { equipped: false, slot: Slot::RightHand })
+      equipment: Some(Equipment {
+        equipped: false,
+        slot: Slot::RightHand,
+        resistances: Resistances::default(),
+        ranged: None,
+        power_bonus: 3,
+        defense_bonus: 0,
+        blessing: Blessing::Uncursed,
+        blessing_known: false,
+        rarity: Rarity::Common,
+      }),
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_fire_helm(x: i32, y: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '[',
+      name: "helmet of fire resistance".to_string(),
+      color:  tcod::colors::FLAME,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Equipment),
+      always_visible: true,
+      equipment: Some(Equipment {
+        equipped: false,
+        slot: Slot::Head,
+        resistances: Resistances { fire: 50, ..Default::default() },
+        ranged: None,
+        power_bonus: 0,
+        defense_bonus: 1,
+        blessing: Blessing::Uncursed,
+        blessing_known: false,
+        rarity: Rarity::Common,
+      }),
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_ranged_weapon(x: i32, y: i32, name: &str, ranged: RangedWeapon) -> Self {
+    Object {
+      x,
+      y,
+      char: '}',
+      name: name.to_string(),
+      color:  tcod::colors::SEPIA,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Equipment),
+      always_visible: true,
+      equipment: Some(Equipment {
+        equipped: false,
+        slot: Slot::RightHand,
+        resistances: Resistances::default(),
+        ranged: Some(ranged),
+        power_bonus: 0,
+        defense_bonus: 0,
+        blessing: Blessing::Uncursed,
+        blessing_known: false,
+        rarity: Rarity::Common,
+      }),
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_equipment(x: i32, y: i32, char: char, name: &str, slot: Slot, power_bonus: i32, defense_bonus: i32) -> Self {
+    Object {
+      x,
+      y,
+      char,
+      name: name.to_string(),
+      color:  tcod::colors::SKY,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Equipment),
+      always_visible: true,
+      equipment: Some(Equipment {
+        equipped: false,
+        slot,
+        resistances: Resistances::default(),
+        ranged: None,
+        power_bonus,
+        defense_bonus,
+        blessing: Blessing::Uncursed,
+        blessing_known: false,
+        rarity: Rarity::Common,
+      }),
+      quantity: 1,
+      charges: None,
+    }
+  }
+
+  pub fn create_bow(x: i32, y: i32) -> Self {
+    Object::create_ranged_weapon(x, y, "bow", RangedWeapon { ammo: Ammo::Arrow, range: 8, damage: 6 })
+  }
+
+  pub fn create_crossbow(x: i32, y: i32) -> Self {
+    Object::create_ranged_weapon(x, y, "crossbow", RangedWeapon { ammo: Ammo::Bolt, range: 10, damage: 9 })
+  }
+
+  pub fn create_sling(x: i32, y: i32) -> Self {
+    Object::create_ranged_weapon(x, y, "sling", RangedWeapon { ammo: Ammo::Stone, range: 6, damage: 4 })
+  }
+
+  pub fn create_ammo(x: i32, y: i32, ammo: Ammo, quantity: i32) -> Self {
+    Object {
+      x,
+      y,
+      char: '{',
+      name: ammo.to_string(),
+      color:  tcod::colors::LIGHT_SEPIA,
+      blocks: false,
+      alive: false,
+      fighter: None,
+      item: Some(Item::Ammo(ammo)),
+      always_visible: true,
+      equipment: None,
+      quantity,
+      charges: None,
     }
   }
 
@@ -134,7 +587,7 @@ impl Object {
     Object {
       x,
       y,
-      char: '<',
+      char: '>',
       name: "stairs".to_string(),
       color:  tcod::colors::WHITE,
       blocks: false,
@@ -142,7 +595,9 @@ impl Object {
       fighter: None,
       item: None,
       always_visible: true,
-      equipment: None
+      equipment: None,
+      quantity: 1,
+      charges: None,
     }
   }
 
@@ -161,6 +616,13 @@ impl Object {
           format!("Equipped by you on {}.", equipment.slot),
           tcod::colors::LIGHT_GREEN,
         );
+        if equipment.is_cursed() {
+          equipment.blessing_known = true;
+          messages.add(
+            format!("You feel a malevolent aura as the {} binds itself to you!", self.name),
+            tcod::colors::RED,
+          );
+        }
       }
     } else {
       messages.add(
@@ -179,7 +641,13 @@ impl Object {
       return;
     };
     if let Some(ref mut equipment) = self.equipment {
-      if equipment.equipped {
+      if equipment.equipped && equipment.is_cursed() {
+          equipment.blessing_known = true;
+          messages.add(
+            format!("You can't remove the {}, it is cursed!", self.name),
+            tcod::colors::RED,
+          );
+      } else if equipment.equipped {
           equipment.equipped = false;
           messages.add(
             format!("Dequipped by you from {}.", equipment.slot),
@@ -252,6 +720,117 @@ impl Object {
     self.name.to_string()
   }
 
+  /// name as known by the player: its random appearance until identified
+  pub fn known_name(&self, identification: &Identification) -> String {
+    match self.item.and_then(|item| identification.appearance(item).filter(|_| !identification.is_identified(item))) {
+      Some(appearance) => appearance.to_string(),
+      None => match self.equipment {
+        Some(equipment) if equipment.blessing_known => format!("{} {}", equipment.blessing, self.name),
+        _ => self.name.to_string(),
+      },
+    }
+  }
+
+  /// weight of the whole stack
+  pub fn weight(&self) -> i32 {
+    self.unit_weight() * self.quantity
+  }
+
+  pub fn unit_weight(&self) -> i32 {
+    match self.equipment {
+      Some(Equipment { ranged: Some(_), .. }) => 25,
+      Some(Equipment { slot: Slot::LeftHand, .. }) => 50,
+      Some(Equipment { slot: Slot::RightHand, .. }) => 30,
+      Some(Equipment { slot: Slot::Head, .. }) => 30,
+      None => self.item.map_or(0, |item| item.weight()),
+    }
+  }
+
+  /// price of a single item of the stack, derived from what the item does
+  pub fn price(&self) -> i32 {
+    self.price_with(self.equipment)
+  }
+
+  /// price as far as the player can tell, without an unknown curse or blessing
+  pub fn apparent_price(&self) -> i32 {
+    self.price_with(self.equipment.map(|equipment| equipment.apparent()))
+  }
+
+  fn price_with(&self, equipment: Option<Equipment>) -> i32 {
+    let base = self.item.map_or(0, |item| item.base_price());
+    let charges = self.charges.map_or(0, |charges| charges * 15);
+    let bonuses = equipment.map_or(0, |equipment| {
+      let ranged = equipment.ranged.map_or(0, |ranged| ranged.damage * 10 + ranged.range * 2);
+      let value = equipment.power_bonus * 30 + equipment.defense_bonus * 30 + equipment.resistances.total() + ranged;
+      cmp::max(value, 0) * equipment.rarity.price_multiplier()
+    });
+    cmp::max(base + charges + bonuses, 1)
+  }
+
+  pub fn rarity(&self) -> Rarity {
+    self.equipment.map_or(Rarity::Common, |equipment| equipment.rarity)
+  }
+
+  /// whether a scroll of identify would teach something about this object
+  pub fn is_unidentified(&self, identification: &Identification) -> bool {
+    let kind_unknown = self.item.map_or(false, |item| !identification.is_identified(item));
+    let blessing_unknown = self.equipment.map_or(false, |equipment| !equipment.blessing_known);
+    kind_unknown || blessing_unknown
+  }
+
+  /// name shown to the player, with the size of the stack if any
+  pub fn display_name(&self, identification: &Identification) -> String {
+    let name = self.known_name(identification);
+    let name = if self.quantity > 1 {
+      format!("{} {}", self.quantity, pluralize(&name))
+    } else {
+      name
+    };
+    // charges are only known once the wand is identified
+    match (self.charges, self.item) {
+      (Some(1), Some(item)) if identification.is_identified(item) => format!("{} (1 charge)", name),
+      (Some(charges), Some(item)) if identification.is_identified(item) => format!("{} ({} charges)", name, charges),
+      _ => name,
+    }
+  }
+
+  /// name used in sentences, e.g. "a healing potion" or "3 healing potions"
+  pub fn display_name_with_article(&self, identification: &Identification) -> String {
+    let name = self.display_name(identification);
+    if self.quantity > 1 {
+      name
+    } else if name.starts_with(|c| "aeiou".contains(c)) {
+      format!("an {}", name)
+    } else {
+      format!("a {}", name)
+    }
+  }
+
+  /// split `count` items off this stack
+  pub fn split(&mut self, count: i32) -> Object {
+    let mut part = self.clone();
+    part.quantity = count;
+    self.quantity -= count;
+    part
+  }
+
+  /// damage dealt when hitting a monster after being thrown
+  pub fn thrown_damage(&self) -> i32 {
+    match self.equipment {
+      Some(equipment) if equipment.ranged.is_none()
+        && (equipment.slot == Slot::LeftHand || equipment.slot == Slot::RightHand) => THROWN_WEAPON_DAMAGE,
+      _ => THROWN_ITEM_DAMAGE,
+    }
+  }
+
+  /// whether `other` can be merged into this stack
+  pub fn stacks_with(&self, other: &Object) -> bool {
+    match (self.item, other.item) {
+      (Some(item), Some(other_item)) => item.is_stackable() && item == other_item && self.name == other.name,
+      _ => false,
+    }
+  }
+
   pub fn always_visible(&self) -> bool {
     self.always_visible
   }
@@ -266,4 +845,28 @@ pub fn is_blocked(x: i32, y: i32, map: &Map, objects: &[Object]) -> bool {
   objects
     .iter()
     .any(|object| object.is_blocked() && object.pos() == (x, y))
+}
+
+/// put an item on the floor, merging it with a matching stack on the same tile
+pub fn place_on_floor(item: Object, collectibles: &mut Vec<Object>) {
+  let stack_id = collectibles
+    .iter()
+    .position(|object| object.pos() == item.pos() && object.stacks_with(&item));
+  match stack_id {
+    Some(stack_id) => collectibles[stack_id].quantity += item.quantity,
+    None => collectibles.push(item),
+  }
+}
+
+/// plural of an item name: "scroll of fireball" becomes "scrolls of fireball"
+pub fn pluralize(name: &str) -> String {
+  let (noun, rest) = match name.find(" of ").or_else(|| name.find(" labeled ")) {
+    Some(index) => name.split_at(index),
+    None => (name, ""),
+  };
+  if noun.ends_with('s') || noun.ends_with('x') || noun.ends_with("ch") || noun.ends_with("sh") {
+    format!("{}es{}", noun, rest)
+  } else {
+    format!("{}s{}", noun, rest)
+  }
 }
\ No newline at end of file
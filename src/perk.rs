@@ -0,0 +1,114 @@
+use crate::class::Class;
+use crate::constants::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Perk {
+  ToughSkin,
+  Gourmand,
+  Cleave,
+  WeaponMaster,
+  FireMastery,
+  StormCaller,
+  Sharpshooter,
+  Assassin,
+}
+
+/// What a perk needs before it can be taken.
+pub struct PerkInfo {
+  pub perk: Perk,
+  pub name: &'static str,
+  /// only this class may take the perk, any class if None
+  pub class: Option<Class>,
+  pub min_level: i32,
+  pub requires: &'static [Perk],
+}
+
+const PERKS: &[PerkInfo] = &[
+  PerkInfo {
+    perk: Perk::ToughSkin,
+    name: "Tough skin",
+    class: None,
+    min_level: 2,
+    requires: &[],
+  },
+  PerkInfo {
+    perk: Perk::Gourmand,
+    name: "Gourmand",
+    class: None,
+    min_level: 2,
+    requires: &[],
+  },
+  PerkInfo {
+    perk: Perk::Cleave,
+    name: "Cleave",
+    class: Some(Class::Warrior),
+    min_level: 2,
+    requires: &[],
+  },
+  PerkInfo {
+    perk: Perk::WeaponMaster,
+    name: "Weapon master",
+    class: Some(Class::Warrior),
+    min_level: 4,
+    requires: &[Perk::Cleave],
+  },
+  PerkInfo {
+    perk: Perk::FireMastery,
+    name: "Fire mastery",
+    class: Some(Class::Mage),
+    min_level: 2,
+    requires: &[],
+  },
+  PerkInfo {
+    perk: Perk::StormCaller,
+    name: "Storm caller",
+    class: Some(Class::Mage),
+    min_level: 4,
+    requires: &[Perk::FireMastery],
+  },
+  PerkInfo {
+    perk: Perk::Sharpshooter,
+    name: "Sharpshooter",
+    class: Some(Class::Rogue),
+    min_level: 2,
+    requires: &[],
+  },
+  PerkInfo {
+    perk: Perk::Assassin,
+    name: "Assassin",
+    class: Some(Class::Rogue),
+    min_level: 4,
+    requires: &[Perk::Sharpshooter],
+  },
+];
+
+impl PerkInfo {
+  pub fn description(&self) -> String {
+    match self.perk {
+      Perk::ToughSkin => format!("+{}% physical resistance", TOUGH_SKIN_RESISTANCE),
+      Perk::Gourmand => format!("food is {}% more nourishing", GOURMAND_BONUS),
+      Perk::Cleave => format!("melee attacks also hit other adjacent enemies for {}% damage", CLEAVE_DAMAGE),
+      Perk::WeaponMaster => format!("+{} attack", WEAPON_MASTER_BONUS),
+      Perk::FireMastery => format!("fire damage of your items and spells +{}%", ELEMENT_MASTERY_BONUS),
+      Perk::StormCaller => format!("lightning damage of your items and spells +{}%", ELEMENT_MASTERY_BONUS),
+      Perk::Sharpshooter => format!("+{} damage with ranged weapons", SHARPSHOOTER_BONUS),
+      Perk::Assassin => format!("thrown items deal {} times the damage", ASSASSIN_MULTIPLIER),
+    }
+  }
+}
+
+pub fn perk_info(perk: Perk) -> &'static PerkInfo {
+  PERKS.iter().find(|info| info.perk == perk).unwrap()
+}
+
+/// perks a character of this class and level can take next
+pub fn available_perks(class: Class, level: i32, taken: &[Perk]) -> Vec<&'static PerkInfo> {
+  PERKS
+    .iter()
+    .filter(|info| !taken.contains(&info.perk))
+    .filter(|info| info.class.map_or(true, |perk_class| perk_class == class))
+    .filter(|info| info.min_level <= level)
+    .filter(|info| info.requires.iter().all(|required| taken.contains(required)))
+    .collect()
+}
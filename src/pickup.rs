@@ -0,0 +1,156 @@
+use crate::constants::INVENTORY_WIDTH;
+use crate::constants::MAX_INVENTORY;
+use crate::game::Game;
+use crate::hud::colored_menu;
+use crate::hud::menu;
+use crate::object::Item;
+use crate::object::Object;
+use crate::player::Player;
+use crate::Tcod;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+/// Groups of items the player can choose to pick up automatically.
+pub enum PickupCategory {
+  Gold,
+  Potions,
+  Scrolls,
+  Wands,
+  Ammo,
+  Food,
+  Corpses,
+  Equipment,
+  Keys,
+}
+
+pub const PICKUP_CATEGORIES: &[PickupCategory] = &[
+  PickupCategory::Gold,
+  PickupCategory::Potions,
+  PickupCategory::Scrolls,
+  PickupCategory::Wands,
+  PickupCategory::Ammo,
+  PickupCategory::Food,
+  PickupCategory::Corpses,
+  PickupCategory::Equipment,
+  PickupCategory::Keys,
+];
+
+impl PickupCategory {
+  pub fn of(item: Item) -> PickupCategory {
+    match item {
+      Item::Gold => PickupCategory::Gold,
+      Item::Heal | Item::ConfusionPotion | Item::PoisonPotion => PickupCategory::Potions,
+      Item::Lightning | Item::Confuse | Item::Fireball | Item::Identify | Item::Recharge
+        | Item::RemoveCurse | Item::MassConfusion | Item::Teleport => PickupCategory::Scrolls,
+      Item::LightningWand | Item::ConfusionWand | Item::FireballWand | Item::DiggingWand => PickupCategory::Wands,
+      Item::Ammo(_) => PickupCategory::Ammo,
+      Item::Ration => PickupCategory::Food,
+      Item::Corpse => PickupCategory::Corpses,
+      Item::Equipment => PickupCategory::Equipment,
+      Item::Key => PickupCategory::Keys,
+    }
+  }
+}
+
+impl fmt::Display for PickupCategory {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      PickupCategory::Gold => write!(f, "gold"),
+      PickupCategory::Potions => write!(f, "potions"),
+      PickupCategory::Scrolls => write!(f, "scrolls"),
+      PickupCategory::Wands => write!(f, "wands"),
+      PickupCategory::Ammo => write!(f, "ammunition"),
+      PickupCategory::Food => write!(f, "food"),
+      PickupCategory::Corpses => write!(f, "corpses"),
+      PickupCategory::Equipment => write!(f, "equipment"),
+      PickupCategory::Keys => write!(f, "keys"),
+    }
+  }
+}
+
+/// indices of the items lying under the player
+fn items_here(player: &Player, collectibles: &[Object]) -> Vec<usize> {
+  collectibles
+    .iter()
+    .enumerate()
+    .filter(|(_, object)| object.pos() == player.pos() && object.item.is_some())
+    .map(|(id, _)| id)
+    .collect()
+}
+
+/// pick up whatever the player chooses from the pile they stand on
+pub fn pick_up(tcod: &mut Tcod, game: &mut Game, player: &mut Player, collectibles: &mut Vec<Object>) {
+  let mut item_ids = items_here(player, collectibles);
+  // the menu can't show more options than the inventory has letters
+  item_ids.truncate(MAX_INVENTORY - 1);
+  let chosen = match item_ids.len() {
+    0 => vec![],
+    1 => item_ids,
+    _ => {
+      let mut options = vec![("Pick up all".to_string(), tcod::colors::WHITE)];
+      options.extend(
+        item_ids
+          .iter()
+          .map(|id| (collectibles[*id].display_name(&game.identification), collectibles[*id].rarity().color())),
+      );
+      match colored_menu("Pick up what?\n", &options, INVENTORY_WIDTH, &mut tcod.root) {
+        Some(0) => item_ids,
+        Some(choice) => vec![item_ids[choice - 1]],
+        None => vec![],
+      }
+    }
+  };
+  pick_up_ids(chosen, game, player, collectibles);
+}
+
+/// pick up every item under the player that belongs to an auto-pickup category
+pub fn auto_pickup(game: &mut Game, player: &mut Player, collectibles: &mut Vec<Object>) {
+  let item_ids = items_here(player, collectibles)
+    .into_iter()
+    .filter(|id| {
+      collectibles[*id]
+        .item
+        .map_or(false, |item| game.auto_pickup.contains(&PickupCategory::of(item)))
+    })
+    .collect();
+  pick_up_ids(item_ids, game, player, collectibles);
+}
+
+fn pick_up_ids(mut item_ids: Vec<usize>, game: &mut Game, player: &mut Player, collectibles: &mut Vec<Object>) {
+  // picking up swaps the last collectible into the freed slot, so go from the end
+  item_ids.sort_unstable_by(|a, b| b.cmp(a));
+  for id in item_ids {
+    player.pick_item_up(id, game, collectibles);
+  }
+}
+
+/// let the player toggle which categories are picked up automatically
+pub fn auto_pickup_options(tcod: &mut Tcod, game: &mut Game) {
+  loop {
+    let options = PICKUP_CATEGORIES
+      .iter()
+      .map(|category| {
+        let mark = if game.auto_pickup.contains(category) { "x" } else { " " };
+        format!("[{}] {}", mark, category)
+      })
+      .collect::<Vec<_>>();
+    let choice = menu(
+      "Auto-pickup: press a key to toggle a category, or any other to close.\n",
+      &options,
+      INVENTORY_WIDTH,
+      &mut tcod.root,
+    );
+    match choice {
+      Some(choice) => {
+        let category = PICKUP_CATEGORIES[choice];
+        if game.auto_pickup.contains(&category) {
+          game.auto_pickup.retain(|picked| *picked != category);
+        } else {
+          game.auto_pickup.push(category);
+        }
+      }
+      None => return,
+    }
+  }
+}
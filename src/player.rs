@@ -1,4 +1,6 @@
 use crate::hud::menu;
+use crate::hud::inventory_menu;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 use crate::target_tile;
 use crate::Tcod;
@@ -7,20 +9,64 @@ use tcod::Console;
 use crate::constants::LEVEL_SCREEN_WIDTH;
 use crate::constants::LEVEL_UP_FACTOR;
 use crate::constants::LEVEL_UP_BASE;
-use crate::constants::FIREBALL_RADIUS;
-use crate::constants::FIREBALL_DAMAGE;
-use crate::constants::CONFUSE_RANGE;
-use crate::constants::CONFUSE_NUM_TURNS;
-use crate::constants::LIGHTNING_RANGE;
-use crate::constants::LIGHTNING_DAMAGE;
-use crate::constants::HEAL_AMOUNT;
 use crate::constants::MAX_INVENTORY;
+use crate::constants::INVENTORY_WIDTH;
+use crate::constants::AMMO_BREAK_CHANCE;
+use crate::constants::THROW_RANGE;
+use crate::constants::POTION_SPLASH_RADIUS;
+use crate::constants::RECHARGE_AMOUNT;
+use crate::constants::DIG_RANGE;
+use crate::constants::MAP_WIDTH;
+use crate::constants::MAP_HEIGHT;
+use crate::constants::START_NUTRITION;
+use crate::constants::MAX_NUTRITION;
+use crate::constants::NUTRITION_PER_TURN;
+use crate::constants::HUNGRY_NUTRITION;
+use crate::constants::STARVATION_DAMAGE;
+use crate::constants::RATION_NUTRITION;
+use crate::constants::CORPSE_NUTRITION;
+use crate::constants::POISON_DAMAGE;
+use crate::constants::TOUGH_SKIN_RESISTANCE;
+use crate::constants::GOURMAND_BONUS;
+use crate::constants::CLEAVE_DAMAGE;
+use crate::constants::WEAPON_MASTER_BONUS;
+use crate::constants::ELEMENT_MASTERY_BONUS;
+use crate::constants::SHARPSHOOTER_BONUS;
+use crate::constants::ASSASSIN_MULTIPLIER;
+use crate::constants::PERK_SCREEN_WIDTH;
+use crate::constants::MANA_REGEN_TURNS;
+use crate::constants::MANA_PER_LEVEL;
+use crate::constants::HP_REGEN_TURNS;
+use crate::constants::HP_REGEN_DIVISOR;
+use crate::constants::SPELL_SCREEN_WIDTH;
+use crate::constants::CARRY_CAPACITY_BASE;
+use crate::constants::CARRY_CAPACITY_PER_POWER;
+use crate::constants::MAX_LOAD_FACTOR;
+use crate::constants::ACTION_COST;
+use crate::constants::BURDENED_ACTION_COST;
+use crate::constants::LOCKPICK_CHANCE;
+use crate::constants::LOCKPICK_TRAP_CHANCE;
+use crate::constants::LOCKPICK_TRAP_DAMAGE;
+use crate::constants::BASH_CHANCE_PER_POWER;
+use crate::constants::BASH_DAMAGE;
+use crate::class::Class;
+use crate::perk::{available_perks, Perk};
+use crate::spell::{spell_from_scroll, spell_info, spells_at_level, Spell};
+use crate::damage::{DamageType, Resistances};
+use crate::effect::{apply_effects, item_effect, monsters_around, use_item_effect, Effect, Target};
 use crate::enemy::Enemy;
+use crate::equipment::Equipment;
 use crate::fighter::Fighter;
 use crate::game::Game;
+use crate::hunger::Hunger;
+use crate::shop::trade;
 use crate::object::Object;
 use crate::object::Item;
-use crate::enemy::Ai;
+use crate::object::place_on_floor;
+use crate::object::pluralize;
+use crate::projectile::projectile_path;
+use std::cmp;
+use tcod::line::Line;
 
 
 enum UseResult {
@@ -32,11 +78,26 @@ enum UseResult {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Player {
   object: Object,
+  class: Class,
+  perks: Vec<Perk>,
+  perk_points: i32,
+  spells: Vec<Spell>,
+  mana: i32,
+  max_mana: i32,
+  // turns since the last point of mana came back
+  mana_counter: i32,
+  // turns since hit points last came back
+  hp_counter: i32,
   level: i32,
+  confused_turns: i32,
+  poisoned_turns: i32,
+  nutrition: i32,
+  gold: i32,
 }
 
 impl Player {
-  pub fn new(x: i32, y: i32) -> Self {
+  pub fn new(x: i32, y: i32, class: Class) -> Self {
+    let stats = class.stats();
     let object = Object { 
       x, 
       y, 
@@ -46,19 +107,35 @@ impl Player {
       blocks: true,
       alive: true,
       fighter:  Some(Fighter {
-        max_hp: 100,
-        hp: 100,
-        defense: 1,
+        max_hp: stats.max_hp,
+        hp: stats.max_hp,
+        defense: stats.defense,
         xp: 0,
-        power: 4
+        power: stats.power,
+        damage_type: DamageType::Physical,
+        resistances: stats.resistances,
       }),
       item: None,
       always_visible: true,
-      equipment: None
+      equipment: None,
+      quantity: 1,
+      charges: None,
     };
     Player {
       object,
-      level: 1
+      class,
+      perks: vec![],
+      perk_points: 0,
+      spells: class.starting_spells(),
+      mana: stats.max_mana,
+      max_mana: stats.max_mana,
+      mana_counter: 0,
+      hp_counter: 0,
+      level: 1,
+      confused_turns: 0,
+      poisoned_turns: 0,
+      nutrition: START_NUTRITION,
+      gold: 0,
     }
   }
 
@@ -67,8 +144,15 @@ impl Player {
   }
 
   pub fn attack(&mut self, target: &mut Enemy, game: &mut Game) {
+    self.strike(target, game, 100);
+  }
+
+  // hit a monster with a percentage of the full attack power
+  fn strike(&mut self, target: &mut Enemy, game: &mut Game, percent: i32) {
     // a simple formula for attack damage
-    let damage = self.get_fighter().map_or(0, |f| f.power) - target.get_fighter().map_or(0, |f| f.defense);
+    let damage = self.power(game) * percent / 100 - target.get_fighter().map_or(0, |f| f.defense);
+    let damage_type = self.get_fighter().map_or(DamageType::Physical, |f| f.damage_type);
+    let damage = target.resisted_damage(damage, damage_type);
     if damage > 0 {
       // make the target take some damage
       game.messages.add(
@@ -87,17 +171,39 @@ impl Player {
     }
   }
 
-  pub fn move_or_attack(&mut self, dx: i32, dy: i32, game: &mut Game, enemies: &mut [Enemy]) {
+  pub fn move_or_attack(&mut self, dx: i32, dy: i32, game: &mut Game, tcod: &mut Tcod, enemies: &mut [Enemy]) {
+    // a confused player stumbles in a random direction
+    let (dx, dy) = if self.confused_turns > 0 {
+      (rand::thread_rng().gen_range(-1, 2), rand::thread_rng().gen_range(-1, 2))
+    } else {
+      (dx, dy)
+    };
     let x = self.object.x + dx;
     let y = self.object.y + dy;
+    if game.map[x as usize][y as usize].is_locked() {
+      self.open_locked_door(x, y, game, tcod);
+      return;
+    }
     
     let target_id = enemies
       .iter()
       .position(|object| object.get_fighter().is_some() && object.pos() == (x, y));
-    // attack if target found, move otherwise
+    // trade with a peaceful shopkeeper, attack if target found, move otherwise
     match target_id {
+      Some(target_id) if enemies[target_id].is_shopkeeper() => {
+        trade(tcod, game, self, &mut enemies[target_id]);
+      }
       Some(target_id) => {
         self.attack(&mut enemies[target_id], game);
+        if self.has_perk(Perk::Cleave) {
+          let (player_x, player_y) = self.pos();
+          for (id, enemy) in enemies.iter_mut().enumerate() {
+            if id != target_id && enemy.get_fighter().is_some() && !enemy.is_shopkeeper()
+              && (enemy.get_x() - player_x).abs() <= 1 && (enemy.get_y() - player_y).abs() <= 1 {
+              self.strike(enemy, game, CLEAVE_DAMAGE);
+            }
+          }
+        }
       }
       None => {
         //clone => dirty 
@@ -111,6 +217,59 @@ impl Player {
     }
   }
 
+  /// a locked door opens with a key, without one the player may try to force it
+  fn open_locked_door(&mut self, x: i32, y: i32, game: &mut Game, tcod: &mut Tcod) {
+    if let Some(key_id) = game.inventory.iter().position(|item| item.item == Some(Item::Key)) {
+      game.take_from_inventory(key_id, 1);
+      game.messages.add("You unlock the door with your key.", tcod::colors::LIGHT_GREEN);
+      unlock_door(x, y, game, tcod);
+      return;
+    }
+    let choice = menu(
+      "The door is locked.\n",
+      &["Pick the lock", "Bash it open", "Leave it alone"],
+      LEVEL_SCREEN_WIDTH,
+      &mut tcod.root,
+    );
+    match choice {
+      Some(0) => {
+        // rogues are twice as good with locks and know how to avoid their traps
+        let (chance, trap_chance) = if self.class == Class::Rogue {
+          (LOCKPICK_CHANCE * 2.0, 0.0)
+        } else {
+          (LOCKPICK_CHANCE, LOCKPICK_TRAP_CHANCE)
+        };
+        if rand::random::<f32>() < chance {
+          game.messages.add("You hear a click, the lock gives way.", tcod::colors::LIGHT_GREEN);
+          unlock_door(x, y, game, tcod);
+        } else if rand::random::<f32>() < trap_chance {
+          game.messages.add(
+            format!("A needle springs from the lock! You take {} damage.", LOCKPICK_TRAP_DAMAGE),
+            tcod::colors::RED,
+          );
+          self.take_damage(LOCKPICK_TRAP_DAMAGE, game);
+        } else {
+          game.messages.add("You fail to pick the lock.", tcod::colors::WHITE);
+        }
+      }
+      Some(1) => {
+        // stronger characters are more likely to break the door, warriors even more so
+        let training = if self.class == Class::Warrior { 2.0 } else { 1.0 };
+        if rand::random::<f32>() < self.power(game) as f32 * BASH_CHANCE_PER_POWER * training {
+          game.messages.add("The door bursts open!", tcod::colors::LIGHT_GREEN);
+          unlock_door(x, y, game, tcod);
+        } else {
+          game.messages.add(
+            format!("The door holds. You bruise your shoulder for {} damage.", BASH_DAMAGE),
+            tcod::colors::RED,
+          );
+          self.take_damage(BASH_DAMAGE, game);
+        }
+      }
+      _ => (),
+    }
+  }
+
   pub fn take_damage(&mut self, damage: i32, game: &mut Game) {
     if let Some(fighter) = self.object.fighter.as_mut() {
       if damage > 0 {
@@ -129,35 +288,90 @@ impl Player {
   }
 
   pub fn pick_item_up(&mut self, object_id: usize, game: &mut Game, collectibles: &mut Vec<Object>) {
-    if game.inventory.len() >= MAX_INVENTORY {
+    let stack_id = game.inventory
+      .iter()
+      .position(|item| item.stacks_with(&collectibles[object_id]));
+    if collectibles[object_id].item == Some(Item::Gold) {
+      // gold goes straight into the purse
+      let gold = collectibles.swap_remove(object_id);
+      game.messages.add(format!("You picked up {} gold.", gold.quantity), tcod::colors::GOLD);
+      self.gold += gold.quantity;
+    } else if !self.can_carry(collectibles[object_id].weight(), game) {
+      game.messages.add(
+        format!(
+          "The {} is too heavy for you to pick up.",
+          collectibles[object_id].known_name(&game.identification)
+        ),
+        tcod::colors::RED,
+      );
+    } else if let Some(stack_id) = stack_id {
+      // merge into the stack already carried, even with a full inventory
+      let item = collectibles.swap_remove(object_id);
+      game.messages.add(format!("You picked up {}!", item.display_name_with_article(&game.identification)), tcod::colors::GREEN);
+      game.inventory[stack_id].quantity += item.quantity;
+    } else if game.inventory.len() >= MAX_INVENTORY {
       game.messages.add(
         format!(
           "Your inventory is full, cannot pick up {}.",
-          collectibles[object_id].name
+          collectibles[object_id].known_name(&game.identification)
         ),
         tcod::colors::RED,
       );
     } else {
       let item = collectibles.swap_remove(object_id);
-      game.messages.add(format!("You picked up a {}!", item.name), tcod::colors::GREEN);
+      game.messages.add(format!("You picked up {}!", item.display_name_with_article(&game.identification)), tcod::colors::GREEN);
       game.inventory.push(item);
     }
   }
 
-  pub fn use_item(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, collectibles: &mut [Object], enemies: &mut [Enemy] ) {
+  pub fn use_item(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, collectibles: &mut Vec<Object>, enemies: &mut [Enemy] ) {
     
     if let Some(item) = game.inventory[inventory_id].item {
+      if game.inventory[inventory_id].charges == Some(0) {
+        game.messages.add(
+          format!("The {} is out of charges.", game.inventory[inventory_id].known_name(&game.identification)),
+          tcod::colors::WHITE,
+        );
+        return;
+      }
       let on_use = match item {
-        Item::Heal => Player::cast_heal,
-        Item::Lightning => Player::cast_lightning,
-        Item::Confuse => Player::cast_confuse,
-        Item::Fireball => Player::cast_fireball,
         Item::Equipment => Player::toggle_equipment,
+        Item::Ammo(_) => Player::cast_fire,
+        Item::Identify => Player::cast_identify,
+        Item::DiggingWand => Player::cast_dig,
+        Item::Recharge => Player::cast_recharge,
+        Item::RemoveCurse => Player::cast_remove_curse,
+        Item::Ration | Item::Corpse => Player::cast_eat,
+        Item::Key => Player::cast_unlock,
+        // gold is never carried in the inventory
+        Item::Gold => return,
+        // these are described by the effect table
+        Item::Heal | Item::Lightning | Item::Confuse | Item::Fireball | Item::ConfusionPotion
+          | Item::LightningWand | Item::ConfusionWand | Item::FireballWand | Item::PoisonPotion
+          | Item::MassConfusion | Item::Teleport => Player::cast_effect,
       };
+      let object = game.inventory[inventory_id].clone();
       match on_use(self, game, tcod, inventory_id, collectibles, enemies) {
         UseResult::UsedUp => {
-          // destroy after use, unless it was cancelled for some reason
-          game.inventory.remove(inventory_id);
+          // using an unknown potion or scroll reveals what it was
+          if game.identification.identify(&object) {
+            game.messages.add(format!("It was a {}.", object.name), tcod::colors::WHITE);
+          }
+          if let Some(spell) = spell_from_scroll(item) {
+            self.learn_spell(spell, game);
+          }
+          // wands spend a charge, anything else is destroyed after use
+          match game.inventory[inventory_id].charges {
+            Some(charges) => {
+              game.inventory[inventory_id].charges = Some(charges - 1);
+              if charges == 1 {
+                game.messages.add(format!("The {} is now inert.", object.name), tcod::colors::LIGHT_GREY);
+              }
+            }
+            None => {
+              game.take_from_inventory(inventory_id, 1);
+            }
+          }
         }
         UseResult::Cancelled => {
           game.messages.add("Cancelled", tcod::colors::WHITE);
@@ -166,123 +380,423 @@ impl Player {
       }
     } else {
       game.messages.add(
-        format!("The {} cannot be used.", game.inventory[inventory_id].name),
+        format!("The {} cannot be used.", game.inventory[inventory_id].known_name(&game.identification)),
         tcod::colors::WHITE,
       );
     }
   }
 
-  fn cast_heal(&mut self, game: &mut Game, _tcod: &mut Tcod, _inventory_id: usize, _collectibles: &mut [Object], _enemies: &mut [Enemy]) -> UseResult {
-    // heal the player
-    if let Some(fighter) = self.get_fighter() {
-      if fighter.hp == fighter.max_hp {
-        game.messages.add("You are already at full health.", tcod::colors::RED);
+  // items defined only by their effects
+  fn cast_effect(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, collectibles: &mut Vec<Object>, enemies: &mut [Enemy]) -> UseResult {
+    let item_effect = match game.inventory[inventory_id].item.and_then(item_effect) {
+      Some(item_effect) => item_effect,
+      None => {
+        game.messages.add("Nothing happens.", tcod::colors::WHITE);
         return UseResult::Cancelled;
       }
-      game.messages
-        .add("Your wounds start to feel better!", tcod::colors::LIGHT_VIOLET);
-        self.heal(HEAL_AMOUNT);
-      return UseResult::UsedUp;
+    };
+    if use_item_effect(item_effect, self, game, tcod, collectibles, enemies) {
+      UseResult::UsedUp
+    } else {
+      UseResult::Cancelled
     }
-    UseResult::Cancelled
   }
 
-  // find closest enemy (inside a maximum range and damage it)
-  fn cast_lightning(&mut self, game: &mut Game, _tcod: &mut Tcod, _inventory_id: usize, _collectibles: &mut [Object], enemies: &mut [Enemy]) -> UseResult {
-    let monster_id = self.closest_monster(_tcod, enemies, LIGHTNING_RANGE);
-    if let Some(monster_id) = monster_id {
-        // zap it!
+  fn cast_identify(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut [Enemy]) -> UseResult {
+    let unknown_ids = game.inventory
+      .iter()
+      .enumerate()
+      .filter(|(id, _)| *id != inventory_id)
+      .filter(|(_, item)| item.is_unidentified(&game.identification))
+      .map(|(id, _)| id)
+      .collect::<Vec<_>>();
+    if unknown_ids.is_empty() {
+      game.messages.add("You have nothing left to identify.", tcod::colors::WHITE);
+      return UseResult::UsedUp;
+    }
+    let unknown_items = unknown_ids
+      .iter()
+      .map(|id| game.inventory[*id].clone())
+      .collect::<Vec<_>>();
+    let choice = inventory_menu(
+      &unknown_items,
+      &game.identification,
+      "Press the key next to an item to identify it, or any other to cancel.\n",
+      &mut tcod.root,
+    );
+    match choice {
+      Some(choice) => {
+        let object = &mut game.inventory[unknown_ids[choice]];
+        let appearance = object.known_name(&game.identification);
+        game.identification.identify(object);
+        if let Some(ref mut equipment) = object.equipment {
+          equipment.blessing_known = true;
+        }
+        let name = object.known_name(&game.identification);
         game.messages.add(
-            format!(
-                "A lightning bolt strikes the {} with a loud thunder! \
-                 The damage is {} hit points.",
-                enemies[monster_id].get_name(), LIGHTNING_DAMAGE
-            ),
-            tcod::colors::LIGHT_BLUE,
+          format!("The {} is a {}.", appearance, name),
+          tcod::colors::LIGHT_GREEN,
         );
-        if let Some(xp) = enemies[monster_id].take_damage(LIGHTNING_DAMAGE, game) {
-          self.object.fighter.as_mut().unwrap().xp += xp;
-        }
         UseResult::UsedUp
+      }
+      None => UseResult::Cancelled,
+    }
+  }
+
+  fn cast_eat(&mut self, game: &mut Game, _tcod: &mut Tcod, inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut [Enemy]) -> UseResult {
+    if self.nutrition > MAX_NUTRITION - HUNGRY_NUTRITION {
+      game.messages.add("You are too full to eat anything.", tcod::colors::WHITE);
+      return UseResult::Cancelled;
+    }
+    let nutrition = match game.inventory[inventory_id].item {
+      Some(Item::Corpse) => CORPSE_NUTRITION,
+      _ => RATION_NUTRITION,
+    };
+    let nutrition = if self.has_perk(Perk::Gourmand) {
+      nutrition * (100 + GOURMAND_BONUS) / 100
+    } else {
+      nutrition
+    };
+    self.nutrition = cmp::min(self.nutrition + nutrition, MAX_NUTRITION);
+    game.messages.add(
+      format!("You eat the {}. That was filling!", game.inventory[inventory_id].name),
+      tcod::colors::LIGHT_GREEN,
+    );
+    UseResult::UsedUp
+  }
+
+  fn cast_remove_curse(&mut self, game: &mut Game, _tcod: &mut Tcod, _inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut [Enemy]) -> UseResult {
+    let mut uncursed = false;
+    for item in game.inventory.iter_mut() {
+      if let Some(ref mut equipment) = item.equipment {
+        if equipment.equipped && equipment.is_cursed() {
+          equipment.uncurse();
+          equipment.blessing_known = true;
+          uncursed = true;
+        }
+      }
+    }
+    if uncursed {
+      game.messages.add("You feel like someone is helping you.", tcod::colors::LIGHT_GREEN);
     } else {
-        // no enemy found within maximum range
-        game.messages
-            .add("No enemy is close enough to strike.", tcod::colors::RED);
+      game.messages.add("You feel like you need some help.", tcod::colors::WHITE);
+    }
+    UseResult::UsedUp
+  }
+
+  // unlock an adjacent door
+  fn cast_unlock(&mut self, game: &mut Game, tcod: &mut Tcod, _inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut [Enemy]) -> UseResult {
+    let (x, y) = self.pos();
+    let door = (-1..=1)
+      .flat_map(|dx| (-1..=1).map(move |dy| (x + dx, y + dy)))
+      .find(|&(x, y)| game.map[x as usize][y as usize].is_locked());
+    match door {
+      Some((x, y)) => {
+        game.messages.add("You unlock the door with your key.", tcod::colors::LIGHT_GREEN);
+        unlock_door(x, y, game, tcod);
+        UseResult::UsedUp
+      }
+      None => {
+        game.messages.add("There is no locked door next to you.", tcod::colors::WHITE);
         UseResult::Cancelled
+      }
     }
   }
 
-  fn cast_confuse(&mut self, game: &mut Game, tcod: &mut Tcod, _inventory_id: usize, collectibles: &mut [Object], enemies: &mut[Enemy] ) -> UseResult {
-        // ask the player for a target to confuse
+  // turn walls into floor along a line starting from the player
+  fn cast_dig(&mut self, game: &mut Game, tcod: &mut Tcod, _inventory_id: usize, collectibles: &mut Vec<Object>, enemies: &mut [Enemy]) -> UseResult {
     game.messages.add(
-        "Left-click an enemy to confuse it, or right-click to cancel.",
-        tcod::colors::LIGHT_CYAN,
+      "Choose a tile to dig towards with the mouse or the cursor and Enter, Escape to cancel.",
+      tcod::colors::LIGHT_CYAN,
+    );
+    let (target_x, target_y) = match target_tile(tcod, game, self, enemies, collectibles, None, None) {
+      Some(tile_pos) => tile_pos,
+      None => return UseResult::Cancelled,
+    };
+    let (dx, dy) = (target_x - self.get_x(), target_y - self.get_y());
+    if (dx, dy) == (0, 0) {
+      return UseResult::Cancelled;
+    }
+    // stretch the line so it always goes as far as the wand can dig
+    let length = cmp::max(dx.abs(), dy.abs());
+    let end = (self.get_x() + dx * DIG_RANGE / length, self.get_y() + dy * DIG_RANGE / length);
+
+    let mut dug = 0;
+    for (x, y) in Line::new(self.pos(), end) {
+      // the outer walls of the map are too hard to dig through
+      if x <= 0 || y <= 0 || x >= MAP_WIDTH - 1 || y >= MAP_HEIGHT - 1 {
+        break;
+      }
+      if game.map[x as usize][y as usize].is_blocked() {
+        game.map[x as usize][y as usize].open();
+        tcod.fov.set(x, y, true, true);
+        tcod.fov_recompute = true;
+        dug += 1;
+      }
+    }
+    if dug > 0 {
+      game.messages.add("The rock crumbles before you!", tcod::colors::LIGHT_SEPIA);
+    } else {
+      game.messages.add("The wand digs through thin air.", tcod::colors::LIGHT_GREY);
+    }
+    UseResult::UsedUp
+  }
+
+  fn cast_recharge(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut [Enemy]) -> UseResult {
+    let wand_ids = game.inventory
+      .iter()
+      .enumerate()
+      .filter(|(id, item)| *id != inventory_id && item.charges.is_some())
+      .map(|(id, _)| id)
+      .collect::<Vec<_>>();
+    if wand_ids.is_empty() {
+      game.messages.add("You have nothing to recharge.", tcod::colors::WHITE);
+      return UseResult::UsedUp;
+    }
+    let wands = wand_ids
+      .iter()
+      .map(|id| game.inventory[*id].clone())
+      .collect::<Vec<_>>();
+    let choice = inventory_menu(
+      &wands,
+      &game.identification,
+      "Press the key next to a wand to recharge it, or any other to cancel.\n",
+      &mut tcod.root,
     );
-    let monster_id = self.target_monster(tcod, game, enemies, collectibles, Some(CONFUSE_RANGE as f32));
-    if let Some(monster_id) = monster_id {
-        let old_ai = enemies[monster_id].get_ai().take().unwrap_or(Ai::Basic);
-        // replace the monster's AI with a "confused" one; after
-        // some turns it will restore the old AI
-        let new_ai = Some(Ai::Confused {
-            previous_ai: Box::new(old_ai),
-            num_turns: CONFUSE_NUM_TURNS,
-        });
-        enemies[monster_id].set_ai(new_ai);
+    match choice {
+      Some(choice) => {
+        let wand = &mut game.inventory[wand_ids[choice]];
+        wand.charges = wand.charges.map(|charges| charges + RECHARGE_AMOUNT);
         game.messages.add(
-            format!(
-                "The eyes of {} look vacant, as he starts to stumble around!",
-                enemies[monster_id].get_name()
-            ),
-            tcod::colors::LIGHT_GREEN,
+          format!("The {} hums with new energy.", wand.known_name(&game.identification)),
+          tcod::colors::LIGHT_AZURE,
         );
         UseResult::UsedUp
+      }
+      None => UseResult::Cancelled,
+    }
+  }
+
+  // shoot the ammunition from the inventory with the equipped ranged weapon
+  fn cast_fire(&mut self, game: &mut Game, tcod: &mut Tcod, inventory_id: usize, collectibles: &mut Vec<Object>, enemies: &mut [Enemy]) -> UseResult {
+    if self.fire(Some(inventory_id), game, tcod, collectibles, enemies) {
+      // the ammunition has already been taken from the stack
+      UseResult::UsedAndKept
     } else {
-        // no enemy fonud within maximum range
-        game.messages
-            .add("No enemy is close enough to strike.", tcod::colors::RED);
-        UseResult::Cancelled
+      UseResult::Cancelled
     }
   }
 
-  fn cast_fireball(&mut self, game: &mut Game, tcod: &mut Tcod, _inventory_id: usize, collectibles: &mut [Object], enemies: &mut[Enemy] ) -> UseResult {
-    // ask the player for a target tile to throw a fireball at
+  /// shoot one piece of ammunition at a target tile, from the given stack or else from the first
+  /// one the weapon can shoot, returns whether a shot was made
+  pub fn fire(&mut self, ammo_id: Option<usize>, game: &mut Game, tcod: &mut Tcod, collectibles: &mut Vec<Object>, enemies: &mut [Enemy]) -> bool {
+    let weapon = game.inventory
+      .iter()
+      .filter_map(|item| item.equipment)
+      .find(|equipment| equipment.equipped && equipment.ranged.is_some());
+    let (ranged, power_bonus) = match weapon {
+      Some(Equipment { ranged: Some(ranged), power_bonus, .. }) => (ranged, power_bonus),
+      _ => {
+        game.messages.add("You have no ranged weapon equipped.", tcod::colors::RED);
+        return false;
+      }
+    };
+    if let Some(Item::Ammo(ammo)) = ammo_id.and_then(|ammo_id| game.inventory[ammo_id].item) {
+      if ammo != ranged.ammo {
+        game.messages.add(format!("You can't shoot {}s, your weapon shoots {}s.", ammo, ranged.ammo), tcod::colors::RED);
+        return false;
+      }
+    }
+    let ammo_id = ammo_id.or_else(|| game.inventory
+      .iter()
+      .position(|item| item.item == Some(Item::Ammo(ranged.ammo))));
+    let ammo_id = match ammo_id {
+      Some(ammo_id) => ammo_id,
+      None => {
+        game.messages.add(format!("You have no {}s left.", ranged.ammo), tcod::colors::RED);
+        return false;
+      }
+    };
+
     game.messages.add(
-      "Left-click a target tile for the fireball, or right-click to cancel.",
+      "Choose a tile to shoot at with the mouse or the cursor and Enter, Tab to cycle enemies, Escape to cancel.",
       tcod::colors::LIGHT_CYAN,
     );
-    let (x, y) = match target_tile(tcod, game, self, enemies, collectibles, None) {
+    let target = match target_tile(tcod, game, self, enemies, collectibles, Some(ranged.range as f32), None) {
       Some(tile_pos) => tile_pos,
-      None => return UseResult::Cancelled,
+      None => return false,
     };
+
+    let mut ammo = game.take_from_inventory(ammo_id, 1);
+
+    let (landing, target_id) = projectile_path(self.pos(), target, &game.map, enemies);
+    match target_id {
+      Some(target_id) => {
+        let bonus = if self.has_perk(Perk::Sharpshooter) { SHARPSHOOTER_BONUS } else { 0 };
+        // a curse or blessing on the weapon weakens or strengthens its shots too
+        let damage = ranged.damage + power_bonus + bonus - enemies[target_id].get_fighter().map_or(0, |f| f.defense);
+        let damage = enemies[target_id].resisted_damage(damage, DamageType::Physical);
+        if damage > 0 {
+          game.messages.add(
+            format!("The {} hits the {} for {} hit points.", ammo.name, enemies[target_id].get_name(), damage),
+            tcod::colors::WHITE,
+          );
+          if let Some(xp) = enemies[target_id].take_damage(damage, game) {
+            self.object.fighter.as_mut().unwrap().xp += xp;
+          }
+        } else {
+          game.messages.add(
+            format!("The {} hits the {} but it has no effect!", ammo.name, enemies[target_id].get_name()),
+            tcod::colors::WHITE,
+          );
+        }
+      }
+      None => {
+        game.messages.add(format!("The {} misses.", ammo.name), tcod::colors::WHITE);
+      }
+    }
+
+    // the ammunition can be picked up again, unless it broke
+    if rand::random::<f32>() < AMMO_BREAK_CHANCE {
+      game.messages.add(format!("The {} breaks.", ammo.name), tcod::colors::LIGHT_GREY);
+    } else {
+      ammo.set_pos(landing.0, landing.1);
+      place_on_floor(ammo, collectibles);
+    }
+    true
+  }
+
+  /// throw an item from the inventory at a target tile, returns whether it was thrown
+  pub fn throw_item(&mut self, inventory_id: usize, game: &mut Game, tcod: &mut Tcod, collectibles: &mut Vec<Object>, enemies: &mut [Enemy]) -> bool {
+    if game.inventory[inventory_id].equipment.map_or(false, |e| e.equipped) {
+      game.messages.add(
+        format!("You have to dequip the {} first.", game.inventory[inventory_id].name),
+        tcod::colors::RED,
+      );
+      return false;
+    }
     game.messages.add(
-      format!(
-        "The fireball explodes, burning everything within {} tiles!",
-        FIREBALL_RADIUS
-      ),
-      tcod::colors::ORANGE,
+      "Choose a tile to throw at with the mouse or the cursor and Enter, Tab to cycle enemies, Escape to cancel.",
+      tcod::colors::LIGHT_CYAN,
     );
+    // potions break and splash around where they land
+    let splash = if game.inventory[inventory_id].item.map_or(false, |item| item.is_potion()) {
+      Some(POTION_SPLASH_RADIUS)
+    } else {
+      None
+    };
+    let target = match target_tile(tcod, game, self, enemies, collectibles, Some(THROW_RANGE as f32), splash) {
+      Some(tile_pos) => tile_pos,
+      None => return false,
+    };
 
-    let mut xp_to_gain = 0;
-    for enemy in enemies {
-      if enemy.distance(x, y) <= FIREBALL_RADIUS as f32 && enemy.get_fighter().is_some() {
-        game.messages.add(
-          format!(
-            "The {} gets burned for {} hit points.",
-            enemy.get_name(), FIREBALL_DAMAGE
-          ),
-          tcod::colors::ORANGE,
-        );
-        if let Some(xp) = enemy.take_damage(FIREBALL_DAMAGE, game) {
-          xp_to_gain += xp;
+    // only one item of a stack is thrown
+    let mut item = game.take_from_inventory(inventory_id, 1);
+    let name = item.known_name(&game.identification);
+
+    let ((x, y), target_id) = projectile_path(self.pos(), target, &game.map, enemies);
+    if let Some(potion) = item.item.filter(|item| item.is_potion()) {
+      game.messages.add(format!("The {} shatters!", name), tcod::colors::LIGHT_BLUE);
+      self.splash(potion, x, y, game, enemies);
+      // the effect gives away what the potion was
+      if game.identification.identify(&item) {
+        game.messages.add(format!("It was a {}.", item.name), tcod::colors::WHITE);
+      }
+      return true;
+    }
+
+    match target_id {
+      Some(target_id) => {
+        let multiplier = if self.has_perk(Perk::Assassin) { ASSASSIN_MULTIPLIER } else { 1 };
+        let damage = item.thrown_damage() * multiplier - enemies[target_id].get_fighter().map_or(0, |f| f.defense);
+        let damage = enemies[target_id].resisted_damage(damage, DamageType::Physical);
+        if damage > 0 {
+          game.messages.add(
+            format!("The {} hits the {} for {} hit points.", name, enemies[target_id].get_name(), damage),
+            tcod::colors::WHITE,
+          );
+          if let Some(xp) = enemies[target_id].take_damage(damage, game) {
+            self.object.fighter.as_mut().unwrap().xp += xp;
+          }
+        } else {
+          game.messages.add(
+            format!("The {} bounces off the {}.", name, enemies[target_id].get_name()),
+            tcod::colors::WHITE,
+          );
         }
       }
+      None => {
+        game.messages.add(format!("The {} lands on the floor.", name), tcod::colors::WHITE);
+      }
+    }
+    item.set_pos(x, y);
+    place_on_floor(item, collectibles);
+    true
+  }
+
+  // apply the effect of a shattered potion to everything around the tile
+  fn splash(&mut self, potion: Item, x: i32, y: i32, game: &mut Game, enemies: &mut [Enemy]) {
+    if let Some(item_effect) = item_effect(potion) {
+      let mut targets = monsters_around(x, y, POTION_SPLASH_RADIUS, enemies);
+      if self.distance(x, y) <= POTION_SPLASH_RADIUS as f32 {
+        targets.push(Target::User);
+      }
+      // only some of the potion reaches each target, halving its healing
+      let effects = item_effect
+        .effects
+        .iter()
+        .map(|effect| match *effect {
+          Effect::Heal { amount } => Effect::Heal { amount: amount / 2 },
+          effect => effect,
+        })
+        .collect::<Vec<_>>();
+      apply_effects(&effects, &targets, self, game, enemies);
     }
-    self.object.fighter.as_mut().unwrap().xp += xp_to_gain;
-    UseResult::UsedUp
   }
 
+  /// things happening to the player at the end of every turn
+  pub fn pass_turn(&mut self, game: &mut Game) {
+    if self.confused_turns > 0 {
+      self.confused_turns -= 1;
+      if self.confused_turns == 0 {
+        game.messages.add("You are no longer confused.", tcod::colors::LIGHT_BLUE);
+      }
+    }
+    if self.poisoned_turns > 0 {
+      self.poisoned_turns -= 1;
+      let damage = self.resisted_damage(POISON_DAMAGE, DamageType::Poison, game);
+      if damage > 0 {
+        game.messages.add("The poison burns in your veins.", tcod::colors::DARK_GREEN);
+        self.take_damage(damage, game);
+      }
+      if self.poisoned_turns == 0 {
+        game.messages.add("You feel less sick.", tcod::colors::LIGHT_GREEN);
+      }
+    }
+    self.regenerate_mana();
+    self.regenerate_hp();
+    self.digest(game);
+  }
+
+  // the hunger clock ticks every turn, and starving hurts
+  fn digest(&mut self, game: &mut Game) {
+    let previous_hunger = self.hunger();
+    self.nutrition = cmp::max(self.nutrition - NUTRITION_PER_TURN, 0);
+    let hunger = self.hunger();
+    if hunger != previous_hunger {
+      game.messages.add(format!("You are {}.", hunger.to_string().to_lowercase()), hunger.color());
+    }
+    if hunger == Hunger::Starving {
+      game.messages.add("You are starving to death!", tcod::colors::RED);
+      self.take_damage(STARVATION_DAMAGE, game);
+    }
+  }
+
+  pub fn hunger(&self) -> Hunger {
+    Hunger::from_nutrition(self.nutrition)
+  }
 
-  fn toggle_equipment(&mut self, game: &mut Game, _tcod: &mut Tcod, inventory_id: usize, _collectibles: &mut [Object], _enemies: &mut[Enemy] ) -> UseResult {
+  fn toggle_equipment(&mut self, game: &mut Game, _tcod: &mut Tcod, inventory_id: usize, _collectibles: &mut Vec<Object>, _enemies: &mut[Enemy] ) -> UseResult {
     let equipment = match game.inventory[inventory_id].equipment {
         Some(equipment) => equipment,
         None => return UseResult::Cancelled,
@@ -291,6 +805,14 @@ impl Player {
     if equipment.equipped {
         game.inventory[inventory_id].dequip(&mut game.messages);
     } else {
+        // free the slot first if something else is equipped there
+        if let Some(old_id) = Object::get_equipped_in_slot(equipment.slot, &game.inventory) {
+          game.inventory[old_id].dequip(&mut game.messages);
+          if game.inventory[old_id].equipment.map_or(false, |e| e.equipped) {
+            // a cursed item can't make room
+            return UseResult::Cancelled;
+          }
+        }
         game.inventory[inventory_id].equip(&mut game.messages);
     }
     UseResult::UsedAndKept
@@ -315,11 +837,11 @@ impl Player {
     closest_enemy
   }
 
-  fn target_monster(&self, tcod: &mut Tcod, game: &mut Game, enemies: &[Enemy], collectibles: &[Object], max_range: Option<f32>) -> Option<usize> {
+  pub fn target_monster(&self, tcod: &mut Tcod, game: &mut Game, enemies: &[Enemy], collectibles: &[Object], max_range: Option<f32>) -> Option<usize> {
     loop {
-      match target_tile(tcod, game, self, enemies, collectibles, max_range) {
+      match target_tile(tcod, game, self, enemies, collectibles, max_range, None) {
         Some((x, y)) => {
-          // return the first clicked monster, otherwise continue looping
+          // return the first chosen monster, otherwise continue looping
           for (id, enemy) in enemies.iter().enumerate() {
             if enemy.pos() == (x, y) && enemy.get_fighter().is_some() {
               return Some(id);
@@ -331,13 +853,56 @@ impl Player {
     }
   }
 
-  pub fn drop_item(&self, inventory_id: usize, game: &mut Game, collectibles: &mut Vec<Object>) {
-    let mut item = game.inventory.remove(inventory_id);
+  pub fn drop_item(&self, inventory_id: usize, game: &mut Game, tcod: &mut Tcod, collectibles: &mut Vec<Object>) {
+    if game.inventory[inventory_id].equipment.map_or(false, |e| e.equipped) {
+      game.inventory[inventory_id].dequip(&mut game.messages);
+      if game.inventory[inventory_id].equipment.map_or(false, |e| e.equipped) {
+        // cursed items stay stuck to the player
+        return;
+      }
+    }
+    let quantity = game.inventory[inventory_id].quantity;
+    let count = if quantity > 1 {
+      // offer to drop only part of the stack
+      let choice = menu(
+        &format!("How many {} do you want to drop?\n", pluralize(&game.inventory[inventory_id].known_name(&game.identification))),
+        &[
+          "One".to_string(),
+          format!("Half ({})", quantity / 2),
+          format!("All ({})", quantity),
+        ],
+        INVENTORY_WIDTH,
+        &mut tcod.root,
+      );
+      match choice {
+        Some(0) => 1,
+        Some(1) => quantity / 2,
+        Some(2) => quantity,
+        _ => return,
+      }
+    } else {
+      1
+    };
+    let mut item = game.take_from_inventory(inventory_id, count);
     item.set_pos(self.get_x(), self.get_y());
-    game.messages.add(format!("You dropped a {}.", item.name), tcod::colors::YELLOW);
-    collectibles.push(item);
+    game.messages.add(format!("You dropped {}.", item.display_name_with_article(&game.identification)), tcod::colors::YELLOW);
+    place_on_floor(item, collectibles);
   }
  
+  pub fn confuse(&mut self, num_turns: i32) {
+    self.confused_turns = num_turns;
+  }
+
+  pub fn poison(&mut self, num_turns: i32) {
+    self.poisoned_turns = num_turns;
+  }
+
+  pub fn gain_xp(&mut self, xp: i32) {
+    if let Some(ref mut fighter) = self.object.fighter {
+      fighter.xp += xp;
+    }
+  }
+
   /// heal by the given amount, without going over the maximum
   pub fn heal(&mut self, amount: i32) {
     if let Some(ref mut fighter) = self.object.fighter {
@@ -390,7 +955,214 @@ impl Player {
         }
         _ => unreachable!(),
       }
+      self.max_mana += MANA_PER_LEVEL;
+      for spell in spells_at_level(self.level) {
+        self.learn_spell(spell, game);
+      }
+      // every level also grants a point to spend in the perk tree
+      self.perk_points += 1;
+      self.choose_perk(tcod, game);
+    }
+  }
+
+  /// natural resistances plus the ones granted by equipped items
+  pub fn resistances(&self, game: &Game) -> Resistances {
+    let mut base = self.get_fighter().map_or(Resistances::default(), |f| f.resistances);
+    if self.has_perk(Perk::ToughSkin) {
+      base.physical += TOUGH_SKIN_RESISTANCE;
+    }
+    self.equipped(game).fold(base, |total, equipment| total.add(&equipment.resistances))
+  }
+
+  /// damage dealt with an item or a spell, raised by elemental perks
+  pub fn item_damage(&self, amount: i32, damage_type: DamageType) -> i32 {
+    let mastered = match damage_type {
+      DamageType::Fire => self.has_perk(Perk::FireMastery),
+      DamageType::Lightning => self.has_perk(Perk::StormCaller),
+      _ => false,
+    };
+    if mastered {
+      amount * (100 + ELEMENT_MASTERY_BONUS) / 100
+    } else {
+      amount
+    }
+  }
+
+  fn regenerate_mana(&mut self) {
+    self.mana_counter += 1;
+    if self.mana_counter >= MANA_REGEN_TURNS {
+      self.mana_counter = 0;
+      self.mana = cmp::min(self.mana + 1, self.max_mana);
+    }
+  }
+
+  // wounds close slowly, unless the player is starving
+  fn regenerate_hp(&mut self) {
+    if self.hunger() == Hunger::Starving {
+      return;
     }
+    self.hp_counter += 1;
+    if self.hp_counter >= HP_REGEN_TURNS {
+      self.hp_counter = 0;
+      let max_hp = self.get_fighter().map_or(0, |f| f.max_hp);
+      self.heal(cmp::max(1, max_hp / HP_REGEN_DIVISOR));
+    }
+  }
+
+  /// true if the player has all their hit points
+  pub fn is_healed(&self) -> bool {
+    self.get_fighter().map_or(true, |f| f.hp >= f.max_hp)
+  }
+
+  pub fn learn_spell(&mut self, spell: Spell, game: &mut Game) {
+    if !self.spells.contains(&spell) {
+      self.spells.push(spell);
+      game.messages.add(
+        format!("You memorize the incantation: you can now cast {}.", spell_info(spell).name),
+        tcod::colors::LIGHT_FUCHSIA,
+      );
+    }
+  }
+
+  /// choose a known spell and cast it, returns true if a spell was cast
+  pub fn cast_spell(&mut self, tcod: &mut Tcod, game: &mut Game, collectibles: &[Object], enemies: &mut [Enemy]) -> bool {
+    if self.spells.is_empty() {
+      game.messages.add("You don't know any spell.", tcod::colors::WHITE);
+      return false;
+    }
+    let options = self
+      .spells
+      .iter()
+      .map(|spell| {
+        let info = spell_info(*spell);
+        format!("{} ({} mana)", info.name, info.mana_cost)
+      })
+      .collect::<Vec<_>>();
+    let choice = menu(
+      &format!("Cast which spell? (mana {}/{})\n", self.mana, self.max_mana),
+      &options,
+      SPELL_SCREEN_WIDTH,
+      &mut tcod.root,
+    );
+    let info = match choice {
+      Some(choice) => spell_info(self.spells[choice]),
+      None => return false,
+    };
+    if self.mana < info.mana_cost {
+      game.messages.add(format!("You don't have enough mana to cast {}.", info.name), tcod::colors::RED);
+      return false;
+    }
+    if use_item_effect(info.effect, self, game, tcod, collectibles, enemies) {
+      self.mana -= info.mana_cost;
+      true
+    } else {
+      false
+    }
+  }
+
+  pub fn get_mana(&self) -> i32 {
+    self.mana
+  }
+
+  pub fn get_max_mana(&self) -> i32 {
+    self.max_mana
+  }
+
+  pub fn has_perk(&self, perk: Perk) -> bool {
+    self.perks.contains(&perk)
+  }
+
+  /// spend a perk point on one of the perks available to the player
+  pub fn choose_perk(&mut self, tcod: &mut Tcod, game: &mut Game) {
+    if self.perk_points == 0 {
+      game.messages.add("You have no perk point to spend.", tcod::colors::WHITE);
+      return;
+    }
+    let available = available_perks(self.class, self.level, &self.perks);
+    if available.is_empty() {
+      game.messages.add("No perk is available to you yet.", tcod::colors::WHITE);
+      return;
+    }
+    let mut options = available
+      .iter()
+      .map(|info| format!("{}: {}", info.name, info.description()))
+      .collect::<Vec<_>>();
+    options.push("Keep the point for later".to_string());
+    let choice = menu(
+      &format!("Choose a perk ({} point(s) left):\n", self.perk_points),
+      &options,
+      PERK_SCREEN_WIDTH,
+      &mut tcod.root,
+    );
+    if let Some(info) = choice.and_then(|choice| available.get(choice)) {
+      self.perks.push(info.perk);
+      self.perk_points -= 1;
+      game.messages.add(format!("You learned {}!", info.name), tcod::colors::YELLOW);
+    }
+  }
+
+  pub fn get_perks(&self) -> &[Perk] {
+    &self.perks
+  }
+
+  /// attack power including the bonuses of equipped items
+  pub fn power(&self, game: &Game) -> i32 {
+    let mut base = self.get_fighter().map_or(0, |f| f.power);
+    if self.has_perk(Perk::WeaponMaster) {
+      base += WEAPON_MASTER_BONUS;
+    }
+    base + self.equipped(game).map(|equipment| equipment.power_bonus).sum::<i32>()
+  }
+
+  /// defense including the bonuses of equipped items
+  pub fn defense(&self, game: &Game) -> i32 {
+    let base = self.get_fighter().map_or(0, |f| f.defense);
+    base + self.equipped(game).map(|equipment| equipment.defense_bonus).sum::<i32>()
+  }
+
+  /// how much the player carries without being slowed down, derived from their strength
+  pub fn capacity(&self) -> i32 {
+    CARRY_CAPACITY_BASE + self.get_fighter().map_or(0, |f| f.power) * CARRY_CAPACITY_PER_POWER
+  }
+
+  pub fn load(&self, game: &Game) -> i32 {
+    game.inventory.iter().map(|item| item.weight()).sum()
+  }
+
+  pub fn is_burdened(&self, game: &Game) -> bool {
+    self.load(game) > self.capacity()
+  }
+
+  pub fn can_carry(&self, weight: i32, game: &Game) -> bool {
+    self.load(game) + weight <= self.capacity() * MAX_LOAD_FACTOR
+  }
+
+  /// true if picking the object up would succeed
+  pub fn can_pick_up(&self, object: &Object, game: &Game) -> bool {
+    object.item == Some(Item::Gold)
+      || (self.can_carry(object.weight(), game)
+        && (game.inventory.len() < MAX_INVENTORY || game.inventory.iter().any(|item| item.stacks_with(object))))
+  }
+
+  /// energy spent by an action, monsters act once for every ACTION_COST
+  pub fn action_cost(&self, game: &Game) -> i32 {
+    if self.is_burdened(game) {
+      BURDENED_ACTION_COST
+    } else {
+      ACTION_COST
+    }
+  }
+
+  fn equipped<'a>(&self, game: &'a Game) -> impl Iterator<Item = Equipment> + 'a {
+    game.inventory
+      .iter()
+      .filter_map(|item| item.equipment)
+      .filter(|equipment| equipment.equipped)
+  }
+
+  /// return the damage actually taken once resistances are applied
+  pub fn resisted_damage(&self, damage: i32, damage_type: DamageType, game: &Game) -> i32 {
+    self.resistances(game).apply(damage, damage_type)
   }
 
   pub fn set_pos(&mut self, x: i32, y: i32) {
@@ -434,7 +1206,34 @@ impl Player {
     self.level
   }
 
+  pub fn get_class(&self) -> Class {
+    self.class
+  }
+
+  pub fn get_gold(&self) -> i32 {
+    self.gold
+  }
+
+  /// spend gold if the player can afford it
+  pub fn pay(&mut self, amount: i32) -> bool {
+    if self.gold < amount {
+      return false;
+    }
+    self.gold -= amount;
+    true
+  }
+
+  pub fn earn(&mut self, amount: i32) {
+    self.gold += amount;
+  }
+
   pub fn level_up_xp(&self) -> i32 {
     LEVEL_UP_BASE + self.level * LEVEL_UP_FACTOR
   }
-}
\ No newline at end of file
+}
+/// replace a locked door by an open doorway
+fn unlock_door(x: i32, y: i32, game: &mut Game, tcod: &mut Tcod) {
+  game.map[x as usize][y as usize].open();
+  tcod.fov.set(x, y, true, true);
+  tcod.fov_recompute = true;
+}
@@ -0,0 +1,24 @@
+use crate::enemy::Enemy;
+use crate::game::Map;
+use tcod::line::Line;
+
+/// Follow a straight line from `from` to `to`. Return the tile where the
+/// projectile ends its course and the enemy it hit on the way, if any.
+pub fn projectile_path(from: (i32, i32), to: (i32, i32), map: &Map, enemies: &[Enemy]) -> ((i32, i32), Option<usize>) {
+  let mut landing = from;
+  for (x, y) in Line::new(from, to) {
+    // walls stop the projectile right before them
+    if map[x as usize][y as usize].is_blocked() {
+      return (landing, None);
+    }
+    landing = (x, y);
+    // the first monster on the way takes the hit
+    let target_id = enemies
+      .iter()
+      .position(|enemy| enemy.get_fighter().is_some() && enemy.pos() == (x, y));
+    if target_id.is_some() {
+      return (landing, target_id);
+    }
+  }
+  (landing, None)
+}
@@ -27,6 +27,11 @@ impl Rect {
     (center_x, center_y)
   }
 
+  /// true if the point is inside the room, walls excluded
+  pub fn contains(&self, x: i32, y: i32) -> bool {
+    x > self.x1 && x < self.x2 && y > self.y1 && y < self.y2
+  }
+
   pub fn intersects_with(&self, other: &Rect) -> bool {
     // returns true if this rectangle intersects with another one
     (self.x1 <= other.x2)
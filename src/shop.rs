@@ -0,0 +1,113 @@
+use crate::constants::MAX_INVENTORY;
+use crate::constants::SELL_PRICE_RATIO;
+use crate::constants::SHOP_WIDTH;
+use crate::enemy::Enemy;
+use crate::game::Game;
+use crate::hud::colored_menu;
+use crate::hud::menu;
+use crate::object::Object;
+use crate::player::Player;
+use crate::Tcod;
+
+/// talk to a shopkeeper, the player can buy and sell as long as they like
+pub fn trade(tcod: &mut Tcod, game: &mut Game, player: &mut Player, shopkeeper: &mut Enemy) {
+  loop {
+    let header = format!(
+      "\"Welcome, adventurer! What can I do for you?\" (you have {} gold)\n",
+      player.get_gold()
+    );
+    match menu(&header, &["Buy", "Sell", "Leave"], SHOP_WIDTH, &mut tcod.root) {
+      Some(0) => buy(tcod, game, player, shopkeeper),
+      Some(1) => sell(tcod, game, player, shopkeeper),
+      _ => return,
+    }
+  }
+}
+
+fn buy(tcod: &mut Tcod, game: &mut Game, player: &mut Player, shopkeeper: &mut Enemy) {
+  if shopkeeper.get_stock().is_empty() {
+    game.messages.add("\"I'm sold out, come back later.\"", tcod::colors::YELLOW);
+    return;
+  }
+  let options = shopkeeper
+    .get_stock()
+    .iter()
+    .map(|item| (price_tag(item, item.price(), game), item.rarity().color()))
+    .collect::<Vec<_>>();
+  let header = format!("Buy what? (you have {} gold)\n", player.get_gold());
+  let stock_id = match colored_menu(&header, &options, SHOP_WIDTH, &mut tcod.root) {
+    Some(stock_id) => stock_id,
+    None => return,
+  };
+
+  let price = shopkeeper.get_stock()[stock_id].price();
+  let stacks = game.inventory.iter().any(|item| item.stacks_with(&shopkeeper.get_stock()[stock_id]));
+  if !stacks && game.inventory.len() >= MAX_INVENTORY {
+    game.messages.add("Your inventory is full.", tcod::colors::RED);
+  } else if !player.can_carry(shopkeeper.get_stock()[stock_id].unit_weight(), game) {
+    game.messages.add("You can't carry any more.", tcod::colors::RED);
+  } else if !player.pay(price) {
+    game.messages.add("\"You can't afford that!\"", tcod::colors::YELLOW);
+  } else {
+    // a stack is sold one item at a time
+    let item = if shopkeeper.get_stock()[stock_id].quantity > 1 {
+      shopkeeper.get_stock_mut()[stock_id].split(1)
+    } else {
+      shopkeeper.get_stock_mut().remove(stock_id)
+    };
+    game.messages.add(
+      format!("You buy {} for {} gold.", item.display_name_with_article(&game.identification), price),
+      tcod::colors::GOLD,
+    );
+    match game.inventory.iter().position(|carried| carried.stacks_with(&item)) {
+      Some(stack_id) => game.inventory[stack_id].quantity += item.quantity,
+      None => game.inventory.push(item),
+    }
+  }
+}
+
+fn sell(tcod: &mut Tcod, game: &mut Game, player: &mut Player, shopkeeper: &mut Enemy) {
+  if game.inventory.is_empty() {
+    game.messages.add("You have nothing to sell.", tcod::colors::WHITE);
+    return;
+  }
+  let options = game
+    .inventory
+    .iter()
+    .map(|item| (price_tag(item, sell_price(item), game), item.rarity().color()))
+    .collect::<Vec<_>>();
+  let inventory_id = match colored_menu("Sell what?\n", &options, SHOP_WIDTH, &mut tcod.root) {
+    Some(inventory_id) => inventory_id,
+    None => return,
+  };
+
+  if game.inventory[inventory_id].equipment.map_or(false, |e| e.equipped) {
+    game.messages.add("You have to take it off first.", tcod::colors::WHITE);
+    return;
+  }
+  let stacks = shopkeeper.get_stock().iter().any(|sold| sold.stacks_with(&game.inventory[inventory_id]));
+  if !stacks && shopkeeper.get_stock().len() >= MAX_INVENTORY {
+    game.messages.add("\"I have no room for that.\"", tcod::colors::YELLOW);
+    return;
+  }
+  let price = sell_price(&game.inventory[inventory_id]);
+  let item = game.take_from_inventory(inventory_id, 1);
+  game.messages.add(
+    format!("You sell {} for {} gold.", item.display_name_with_article(&game.identification), price),
+    tcod::colors::GOLD,
+  );
+  player.earn(price);
+  let stock = shopkeeper.get_stock_mut();
+  match stock.iter().position(|sold| sold.stacks_with(&item)) {
+    Some(stack_id) => stock[stack_id].quantity += item.quantity,
+    None => stock.push(item),
+  }
+}
+
+fn sell_price(item: &Object) -> i32 {
+  std::cmp::max(item.price() / SELL_PRICE_RATIO, 1)
+}
+
+fn price_tag(item: &Object, price: i32, game: &Game) -> String {
+  format!("{} - {} gold", item.display_name(&game.identification), price)
+}
@@ -0,0 +1,114 @@
+use crate::constants::*;
+use crate::damage::DamageType;
+use crate::effect::{Effect, ItemEffect, Targeting};
+use crate::effect::{CONFUSION, FIREBALL, LIGHTNING, MASS_CONFUSION, TELEPORT};
+use crate::object::Item;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Spell {
+  MagicMissile,
+  MinorHeal,
+  LightningBolt,
+  Confusion,
+  Fireball,
+  Blink,
+  MassConfusion,
+}
+
+/// How a spell is cast and learned. Spells share their effects with scrolls.
+pub struct SpellInfo {
+  pub spell: Spell,
+  pub name: &'static str,
+  pub mana_cost: i32,
+  pub effect: &'static ItemEffect,
+  /// reading this scroll teaches the spell
+  pub scroll: Option<Item>,
+  /// reaching this level teaches the spell
+  pub level: Option<i32>,
+}
+
+const MAGIC_MISSILE: ItemEffect = ItemEffect {
+  targeting: Targeting::Nearest { range: MAGIC_MISSILE_RANGE },
+  effects: &[Effect::Damage { amount: MAGIC_MISSILE_DAMAGE, damage_type: DamageType::Physical }],
+  message: Some(("A glowing dart flies from your fingertips!", tcod::colors::LIGHT_FUCHSIA)),
+};
+
+const MINOR_HEAL: ItemEffect = ItemEffect {
+  targeting: Targeting::User,
+  effects: &[Effect::Heal { amount: MINOR_HEAL_AMOUNT }],
+  message: None,
+};
+
+const SPELLS: &[SpellInfo] = &[
+  SpellInfo {
+    spell: Spell::MagicMissile,
+    name: "Magic missile",
+    mana_cost: 5,
+    effect: &MAGIC_MISSILE,
+    scroll: None,
+    level: None,
+  },
+  SpellInfo {
+    spell: Spell::MinorHeal,
+    name: "Minor heal",
+    mana_cost: 10,
+    effect: &MINOR_HEAL,
+    scroll: None,
+    level: Some(3),
+  },
+  SpellInfo {
+    spell: Spell::LightningBolt,
+    name: "Lightning bolt",
+    mana_cost: 15,
+    effect: &LIGHTNING,
+    scroll: Some(Item::Lightning),
+    level: None,
+  },
+  SpellInfo {
+    spell: Spell::Confusion,
+    name: "Confusion",
+    mana_cost: 10,
+    effect: &CONFUSION,
+    scroll: Some(Item::Confuse),
+    level: None,
+  },
+  SpellInfo {
+    spell: Spell::Fireball,
+    name: "Fireball",
+    mana_cost: 25,
+    effect: &FIREBALL,
+    scroll: Some(Item::Fireball),
+    level: None,
+  },
+  SpellInfo {
+    spell: Spell::Blink,
+    name: "Blink",
+    mana_cost: 20,
+    effect: &TELEPORT,
+    scroll: Some(Item::Teleport),
+    level: None,
+  },
+  SpellInfo {
+    spell: Spell::MassConfusion,
+    name: "Mass confusion",
+    mana_cost: 30,
+    effect: &MASS_CONFUSION,
+    scroll: Some(Item::MassConfusion),
+    level: None,
+  },
+];
+
+pub fn spell_info(spell: Spell) -> &'static SpellInfo {
+  SPELLS.iter().find(|info| info.spell == spell).unwrap()
+}
+
+/// the spell taught by reading a scroll, if any
+pub fn spell_from_scroll(item: Item) -> Option<Spell> {
+  SPELLS.iter().find(|info| info.scroll == Some(item)).map(|info| info.spell)
+}
+
+/// spells learned when reaching a level
+pub fn spells_at_level(level: i32) -> Vec<Spell> {
+  SPELLS.iter().filter(|info| info.level == Some(level)).map(|info| info.spell).collect()
+}
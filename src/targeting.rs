@@ -0,0 +1,89 @@
+use crate::constants::*;
+use crate::enemy::Enemy;
+use crate::game::Game;
+use crate::object::Object;
+use crate::player::Player;
+use crate::target_tile;
+use crate::Tcod;
+use tcod::console::*;
+
+/// positions of the visible enemies in range, the closest first
+pub fn visible_targets(tcod: &Tcod, player: &Player, enemies: &[Enemy], max_range: Option<f32>) -> Vec<(i32, i32)> {
+  let mut targets = enemies
+    .iter()
+    .filter(|enemy| enemy.get_fighter().is_some() && tcod.fov.is_in_fov(enemy.get_x(), enemy.get_y()))
+    .filter(|enemy| max_range.map_or(true, |range| player.distance(enemy.get_x(), enemy.get_y()) <= range))
+    .map(|enemy| enemy.pos())
+    .collect::<Vec<_>>();
+  targets.sort_by(|a, b| {
+    player
+      .distance(a.0, a.1)
+      .partial_cmp(&player.distance(b.0, b.1))
+      .unwrap()
+  });
+  targets
+}
+
+/// what the player knows is on a tile: monsters, items and terrain
+pub fn describe_tile(x: i32, y: i32, tcod: &Tcod, game: &Game, player: &Player, enemies: &[Enemy], collectibles: &[Object]) -> String {
+  let in_fov = tcod.fov.is_in_fov(x, y);
+  let tile = &game.map[x as usize][y as usize];
+  if !in_fov && !tile.is_explored() {
+    return "unexplored".to_string();
+  }
+  let mut names = vec![];
+  if player.pos() == (x, y) {
+    names.push("you".to_string());
+  }
+  if in_fov {
+    names.extend(
+      enemies
+        .iter()
+        .filter(|enemy| enemy.get_fighter().is_some() && enemy.pos() == (x, y))
+        .map(|enemy| enemy.get_name()),
+    );
+  }
+  names.extend(
+    collectibles
+      .iter()
+      .filter(|object| object.pos() == (x, y) && (in_fov || object.always_visible()))
+      .map(|object| object.display_name(&game.identification)),
+  );
+  let terrain = if tile.is_locked() {
+    "locked door"
+  } else if tile.is_blocked() {
+    "wall"
+  } else {
+    "floor"
+  };
+  names.push(terrain.to_string());
+  names.join(", ")
+}
+
+/// tint the tiles in range and in the area of effect, and highlight the cursor
+pub fn draw_cursor(tcod: &mut Tcod, player: &Player, cursor: (i32, i32), max_range: Option<f32>, radius: Option<i32>) {
+  for y in 0..MAP_HEIGHT {
+    for x in 0..MAP_WIDTH {
+      if !tcod.fov.is_in_fov(x, y) {
+        continue;
+      }
+      if max_range.map_or(false, |range| player.distance(x, y) <= range) {
+        tcod.root.set_char_background(x, y, COLOR_TARGET_RANGE, BackgroundFlag::Add);
+      }
+      let (dx, dy) = (x - cursor.0, y - cursor.1);
+      if radius.map_or(false, |radius| ((dx * dx + dy * dy) as f32).sqrt() <= radius as f32) {
+        tcod.root.set_char_background(x, y, COLOR_TARGET_AREA, BackgroundFlag::Add);
+      }
+    }
+  }
+  tcod.root.set_char_background(cursor.0, cursor.1, COLOR_CURSOR, BackgroundFlag::Set);
+}
+
+/// move a cursor around to see what is on the map, until the player presses Escape
+pub fn look(tcod: &mut Tcod, game: &mut Game, player: &Player, enemies: &[Enemy], collectibles: &[Object]) {
+  game.messages.add(
+    "Move the cursor to look around, Tab jumps between enemies, Escape to stop.",
+    tcod::colors::LIGHT_CYAN,
+  );
+  while target_tile(tcod, game, player, enemies, collectibles, None, None).is_some() {}
+}
@@ -4,6 +4,8 @@ use serde::{Deserialize, Serialize};
 pub struct Tile {
   blocked: bool,
   block_sight: bool,
+  locked: bool,
+  explored: bool,
 }
 
 impl Tile {
@@ -11,6 +13,8 @@ impl Tile {
     Tile {
       blocked: false,
       block_sight: false,
+      locked: false,
+      explored: false,
     }
   }
 
@@ -18,6 +22,18 @@ impl Tile {
     Tile {
       blocked: true,
       block_sight: true,
+      locked: false,
+      explored: false,
+    }
+  }
+
+  /// a locked door behaves like a wall until it is opened
+  pub fn locked_door() -> Self {
+    Tile {
+      blocked: true,
+      block_sight: true,
+      locked: true,
+      explored: false,
     }
   }
 
@@ -28,4 +44,24 @@ impl Tile {
   pub fn is_block_sight(&self) -> bool {
     self.block_sight
   }
-}
\ No newline at end of file
+
+  pub fn is_locked(&self) -> bool {
+    self.locked
+  }
+
+  /// true once the player has seen the tile
+  pub fn is_explored(&self) -> bool {
+    self.explored
+  }
+
+  pub fn explore(&mut self) {
+    self.explored = true;
+  }
+
+  /// turn a wall or a locked door into floor, the player still remembers the tile
+  pub fn open(&mut self) {
+    self.blocked = false;
+    self.block_sight = false;
+    self.locked = false;
+  }
+}